@@ -9,7 +9,7 @@ fn main() {
     // set volume
     rda5807m.set_volume(1).unwrap();
     // get freq
-    let freq = rda5807m.get_frequency().unwrap();
+    let _freq = rda5807m.get_frequency().unwrap();
     // set freq
     rda5807m.set_frequency(101700).unwrap();
     // seek up
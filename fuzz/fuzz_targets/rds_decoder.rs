@@ -0,0 +1,52 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rda5807m::rds::rtplus::RtPlusContentType;
+use rda5807m::rds::sync::{BlockQuality, CorrectedGroup};
+use rda5807m::rds::RdsDecoder;
+
+// first byte: RBDS mode, then per group 8 bytes of blocks and one byte with
+// two quality bits per block
+fuzz_target!(|data: &[u8]| {
+    let Some((&mode, groups)) = data.split_first() else {
+        return;
+    };
+    let mut decoder = RdsDecoder::new();
+    decoder.set_rbds(mode & 1 != 0);
+    for chunk in groups.chunks_exact(9) {
+        let mut blocks = [0; 4];
+        for (block, bytes) in blocks.iter_mut().zip(chunk.chunks_exact(2)) {
+            *block = u16::from_be_bytes([bytes[0], bytes[1]]);
+        }
+        let quality = chunk[8];
+        if quality == 0 {
+            decoder.decode(&blocks);
+        } else {
+            let mut group = CorrectedGroup {
+                blocks,
+                quality: [BlockQuality::Valid; 4],
+            };
+            for (i, q) in group.quality.iter_mut().enumerate() {
+                *q = match (quality >> (i * 2)) & 0b11 {
+                    0 => BlockQuality::Valid,
+                    1 => BlockQuality::Corrected(2),
+                    2 => BlockQuality::Corrected(5),
+                    _ => BlockQuality::Uncorrectable,
+                };
+            }
+            decoder.decode_corrected(&group);
+        }
+
+        let _ = decoder.ps();
+        let _ = decoder.rt();
+        let _ = decoder.pty_name();
+        let _ = decoder.call_sign();
+        let _ = decoder.country();
+        let _ = decoder.long_ps();
+        for code in 0..64 {
+            let _ = decoder.rt_plus_text(RtPlusContentType::from_u8(code));
+        }
+        while decoder.poll_event().is_some() {}
+        while decoder.poll_tmc_message().is_some() {}
+    }
+});
@@ -0,0 +1,11 @@
+#![no_main]
+
+use std::io::Cursor;
+
+use libfuzzer_sys::fuzz_target;
+use rda5807m::rds::io::replay;
+use rda5807m::rds::RdsDecoder;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = replay(Cursor::new(data), &mut RdsDecoder::new());
+});
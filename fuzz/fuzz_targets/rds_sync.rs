@@ -0,0 +1,21 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rda5807m::rds::sync::BlockSync;
+use rda5807m::rds::RdsDecoder;
+
+// first byte: maximum burst length, then the bit stream, MSB first
+fuzz_target!(|data: &[u8]| {
+    let Some((&max_burst, bytes)) = data.split_first() else {
+        return;
+    };
+    let mut sync = BlockSync::new(u32::from(max_burst % 6));
+    let mut decoder = RdsDecoder::new();
+    for byte in bytes {
+        for bit in (0..8).rev() {
+            if let Some(group) = sync.push_bit(byte & (1 << bit) != 0) {
+                decoder.decode_corrected(&group);
+            }
+        }
+    }
+});
@@ -0,0 +1,169 @@
+use crate::register_address::TuningBitFlag;
+
+// 波段 (REG 0x03 BAND[1:0])
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum Band {
+    // 87-108MHz(Us/Europe)
+    #[default]
+    Europe,
+    // 76-91MHz(Japan)
+    Japan,
+    // 76-108MHz(world wide)
+    WorldWide,
+    // 65-76MHz(East Europe)
+    EastEurope,
+}
+
+impl Band {
+    pub fn from_bits(bits: u8) -> Self {
+        match bits & 0b11 {
+            0b00 => Band::Europe,
+            0b01 => Band::Japan,
+            0b10 => Band::WorldWide,
+            _ => Band::EastEurope,
+        }
+    }
+
+    pub fn bits(self) -> u8 {
+        match self {
+            Band::Europe => 0b00,
+            Band::Japan => 0b01,
+            Band::WorldWide => 0b10,
+            Band::EastEurope => 0b11,
+        }
+    }
+
+    // Value of the band field already shifted into place for REG 0x03
+    pub fn register_value(self) -> u16 {
+        (self.bits() as u16) << TuningBitFlag::BAND_SHIFT
+    }
+
+    /// Lowest frequency of the band in kHz (channel 0)
+    pub fn start_khz(self) -> u32 {
+        match self {
+            Band::Europe => 87_000,
+            Band::Japan | Band::WorldWide => 76_000,
+            Band::EastEurope => 65_000,
+        }
+    }
+
+    /// Highest frequency of the band in kHz
+    pub fn end_khz(self) -> u32 {
+        match self {
+            Band::Europe | Band::WorldWide => 108_000,
+            Band::Japan => 91_000,
+            Band::EastEurope => 76_000,
+        }
+    }
+
+    /// Whether `freq` in kHz lies within the band
+    pub fn contains(self, freq: u32) -> bool {
+        (self.start_khz()..=self.end_khz()).contains(&freq)
+    }
+}
+
+// 频率间隔 (REG 0x03 SPACE[1:0])
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum Spacing {
+    #[default]
+    Khz100,
+    Khz200,
+    Khz50,
+    Khz25,
+}
+
+impl Spacing {
+    pub fn from_bits(bits: u8) -> Self {
+        match bits & 0b11 {
+            0b00 => Spacing::Khz100,
+            0b01 => Spacing::Khz200,
+            0b10 => Spacing::Khz50,
+            _ => Spacing::Khz25,
+        }
+    }
+
+    pub fn bits(self) -> u8 {
+        match self {
+            Spacing::Khz100 => 0b00,
+            Spacing::Khz200 => 0b01,
+            Spacing::Khz50 => 0b10,
+            Spacing::Khz25 => 0b11,
+        }
+    }
+
+    pub fn register_value(self) -> u16 {
+        self.bits() as u16
+    }
+
+    pub fn khz(self) -> u32 {
+        match self {
+            Spacing::Khz100 => 100,
+            Spacing::Khz200 => 200,
+            Spacing::Khz50 => 50,
+            Spacing::Khz25 => 25,
+        }
+    }
+}
+
+// CHAN is a 10 bit field
+pub const MAX_CHANNEL: u16 = 0x3ff;
+
+/// Frequency in kHz of `chan` on the given band and spacing
+pub fn channel_to_frequency(band: Band, spacing: Spacing, chan: u16) -> u32 {
+    band.start_khz() + spacing.khz() * chan as u32
+}
+
+/// Channel number of `freq` (kHz), frequencies below the band start map to channel 0
+pub fn frequency_to_channel(band: Band, spacing: Spacing, freq: u32) -> u16 {
+    let chan = freq.saturating_sub(band.start_khz()) / spacing.khz();
+    chan.min(MAX_CHANNEL as u32) as u16
+}
+
+/// Highest channel number that is still inside the band
+pub fn last_channel(band: Band, spacing: Spacing) -> u16 {
+    frequency_to_channel(band, spacing, band.end_khz())
+}
+
+/// Whether `freq` (kHz) is inside the band and below its last channel. With
+/// 25kHz spacing the 10 bit CHAN field ends before 76 - 108MHz does.
+pub fn is_tunable(band: Band, spacing: Spacing, freq: u32) -> bool {
+    band.contains(freq) && freq <= channel_to_frequency(band, spacing, last_channel(band, spacing))
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum Direction {
+    Up,
+    Down,
+}
+
+/// Channel `steps` grid steps away from `chan`. At the band edges the result
+/// either wraps to the other edge or stays at the edge.
+pub fn step_channel(
+    band: Band,
+    spacing: Spacing,
+    chan: u16,
+    direction: Direction,
+    steps: u16,
+    wrap: bool,
+) -> u16 {
+    let last = last_channel(band, spacing) as i32;
+    let chan = (chan as i32).min(last);
+    let target = match direction {
+        Direction::Up => chan + steps as i32,
+        Direction::Down => chan - steps as i32,
+    };
+    let target = if wrap {
+        target.rem_euclid(last + 1)
+    } else {
+        target.clamp(0, last)
+    };
+    target as u16
+}
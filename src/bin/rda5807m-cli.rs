@@ -0,0 +1,472 @@
+// Command line tool for an RDA5807M on a Linux I²C bus, built with the `cli`
+// feature:
+//
+//     rda5807m-cli --bus /dev/i2c-1 tune 101.7
+//     rda5807m-cli --json info
+
+use std::fmt;
+use std::process::ExitCode;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use clap::{Parser, Subcommand, ValueEnum};
+use linux_embedded_hal::i2cdev::linux::LinuxI2CError;
+use linux_embedded_hal::{Delay, I2cdev};
+use rda5807m::rds::charset;
+use rda5807m::rds::clock::ClockTime;
+use rda5807m::rds::RdsDecoder;
+use rda5807m::trace::{fields, register_name, Field, REGISTER_COUNT};
+use rda5807m::{Direction, Error, Rda5708m, RegisterDump, ScanMethod, ScanOptions};
+use serde_json::{json, Value};
+
+const TUNE_TIMEOUT_MS: u32 = 500;
+const SEEK_TIMEOUT_MS: u32 = 5_000;
+const RDS_POLL_INTERVAL: Duration = Duration::from_millis(20);
+const MAX_STATIONS: usize = 64;
+
+type Radio = Rda5708m<I2cdev>;
+
+#[derive(Parser)]
+#[command(version, about = "Control an RDA5807M FM receiver over Linux I²C")]
+struct Cli {
+    /// I²C bus device
+    #[arg(short, long, default_value = "/dev/i2c-1")]
+    bus: String,
+    /// Device address, decimal or 0x prefixed hex
+    #[arg(short, long, default_value = "0x11", value_parser = parse_u8)]
+    address: u8,
+    /// Print JSON instead of text
+    #[arg(long, global = true)]
+    json: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Show power, tuning, volume and signal
+    Info,
+    /// Tune to a frequency in MHz, powering the chip up if needed
+    Tune { mhz: f64 },
+    /// Seek to the next station
+    Seek {
+        direction: SeekDirection,
+        /// Stop at the band edge instead of wrapping around
+        #[arg(long)]
+        no_wrap: bool,
+    },
+    /// List the stations of the current band
+    Scan {
+        /// Tune every channel instead of seeking
+        #[arg(long)]
+        step: bool,
+        /// Listen this many milliseconds for PI/PS on each station
+        #[arg(long, default_value_t = 0)]
+        rds_ms: u32,
+    },
+    /// Show or set the volume (0 - 15)
+    Volume { level: Option<u8> },
+    /// Show or set mute
+    Mute { state: Option<Switch> },
+    /// Print PS, RadioText and clock time as they are received
+    Rds {
+        /// Stop after this many seconds
+        #[arg(long)]
+        seconds: Option<u64>,
+    },
+    /// Print all registers
+    DumpRegisters,
+    /// Write a register, values are decimal or 0x prefixed hex
+    SetRegister {
+        #[arg(value_parser = parse_u8)]
+        register: u8,
+        #[arg(value_parser = parse_u16)]
+        value: u16,
+        /// Also write the read only registers 0x00 and 0x0A - 0x0F
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(Copy, Clone, ValueEnum)]
+enum SeekDirection {
+    Up,
+    Down,
+}
+
+#[derive(Copy, Clone, ValueEnum)]
+enum Switch {
+    On,
+    Off,
+}
+
+#[derive(Debug)]
+enum CliError {
+    Open(String, LinuxI2CError),
+    Radio(Error<LinuxI2CError>),
+    InvalidArgument(String),
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Open(bus, e) => write!(f, "cannot open {}: {}", bus, e),
+            CliError::Radio(Error::I2C(e)) => write!(f, "I²C transfer failed: {}", e),
+            CliError::Radio(Error::ReadOnlyRegister(register)) => write!(
+                f,
+                "register 0x{:02X} is read only, use --force to write it anyway",
+                register
+            ),
+            CliError::Radio(Error::NotPowered) => {
+                f.write_str("the radio is off, tune to a frequency first")
+            }
+            CliError::Radio(e) => write!(f, "{}", e),
+            CliError::InvalidArgument(message) => f.write_str(message),
+        }
+    }
+}
+
+impl From<Error<LinuxI2CError>> for CliError {
+    fn from(e: Error<LinuxI2CError>) -> Self {
+        CliError::Radio(e)
+    }
+}
+
+fn parse_u16(text: &str) -> Result<u16, String> {
+    let parsed = match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        Some(hex) => u16::from_str_radix(hex, 16),
+        None => text.parse(),
+    };
+    parsed.map_err(|e| format!("invalid number `{}`: {}", text, e))
+}
+
+fn parse_u8(text: &str) -> Result<u8, String> {
+    let value = parse_u16(text)?;
+    u8::try_from(value).map_err(|_| format!("`{}` is larger than 255", text))
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match run(&cli) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(cli: &Cli) -> Result<(), CliError> {
+    let i2c = I2cdev::new(&cli.bus).map_err(|e| CliError::Open(cli.bus.clone(), e))?;
+    let json = cli.json;
+    let mut radio = Rda5708m::new(i2c, cli.address);
+    match cli.command {
+        // register access works without the id check, e.g. on a misbehaving chip
+        Command::DumpRegisters => return dump_registers(&mut radio, json),
+        Command::SetRegister {
+            register,
+            value,
+            force,
+        } => {
+            if register as usize >= REGISTER_COUNT {
+                return Err(CliError::InvalidArgument(format!(
+                    "register 0x{:02X} does not exist",
+                    register
+                )));
+            }
+            return set_register(&mut radio, register, value, force, json);
+        }
+        _ => {}
+    }
+
+    radio.verify_id()?;
+    match cli.command {
+        Command::Info => info(&mut radio, json),
+        Command::Tune { mhz } => tune(&mut radio, mhz, json),
+        Command::Seek { direction, no_wrap } => seek(&mut radio, direction, !no_wrap, json),
+        Command::Scan { step, rds_ms } => scan(&mut radio, step, rds_ms, json),
+        Command::Volume { level } => volume(&mut radio, level, json),
+        Command::Mute { state } => mute(&mut radio, state, json),
+        Command::Rds { seconds } => rds(&mut radio, seconds.map(Duration::from_secs), json),
+        Command::DumpRegisters | Command::SetRegister { .. } => unreachable!(),
+    }
+}
+
+fn print(json: bool, value: Value, human: impl FnOnce() -> String) {
+    if json {
+        println!("{}", value);
+    } else {
+        println!("{}", human());
+    }
+}
+
+fn mhz(khz: u32) -> String {
+    format!("{:.2} MHz", khz as f64 / 1000.0)
+}
+
+// RDS bytes to text, trailing spaces removed
+fn text(bytes: &[u8]) -> String {
+    let text: String = bytes.iter().map(|b| charset::to_char(*b)).collect();
+    text.trim_end().to_string()
+}
+
+fn clock_text(clock: &ClockTime) -> String {
+    let offset = clock.offset.unsigned_abs();
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02} UTC{}{}:{:02}",
+        clock.year,
+        clock.month,
+        clock.day,
+        clock.hour,
+        clock.minute,
+        if clock.offset < 0 { '-' } else { '+' },
+        offset / 2,
+        offset % 2 * 30
+    )
+}
+
+fn ensure_powered(radio: &mut Radio) -> Result<(), CliError> {
+    if radio.snapshot()?.enabled {
+        Ok(())
+    } else {
+        Err(Error::NotPowered.into())
+    }
+}
+
+fn info(radio: &mut Radio, json: bool) -> Result<(), CliError> {
+    let state = radio.snapshot()?;
+    let signal = radio.get_signal_sample()?;
+    let value = json!({
+        "state": state,
+        "signal": {
+            "rssi": signal.rssi,
+            "stereo": signal.stereo,
+            "station": signal.fm_true,
+        },
+    });
+    print(json, value, || {
+        format!(
+            "power:     {}\n\
+             frequency: {}\n\
+             band:      {:?} ({} - {}), {} kHz spacing\n\
+             volume:    {}{}\n\
+             signal:    RSSI {} dBµV, {}{}",
+            if state.enabled { "on" } else { "off" },
+            mhz(state.frequency),
+            state.band,
+            mhz(state.band.start_khz()),
+            mhz(state.band.end_khz()),
+            state.spacing.khz(),
+            state.volume,
+            if state.mute { " (muted)" } else { "" },
+            signal.rssi,
+            if signal.stereo { "stereo" } else { "mono" },
+            if signal.fm_true { ", station" } else { "" },
+        )
+    });
+    Ok(())
+}
+
+fn tune(radio: &mut Radio, mhz_value: f64, json: bool) -> Result<(), CliError> {
+    if !radio.snapshot()?.enabled {
+        radio.start()?;
+    }
+    radio.set_frequency((mhz_value * 1000.0).round() as u32)?;
+    radio.wait_tune_complete(&mut Delay, TUNE_TIMEOUT_MS)?;
+    let frequency = radio.get_frequency()?;
+    print(json, json!({ "frequency": frequency }), || {
+        format!("tuned to {}", mhz(frequency))
+    });
+    Ok(())
+}
+
+fn seek(
+    radio: &mut Radio,
+    direction: SeekDirection,
+    wrap: bool,
+    json: bool,
+) -> Result<(), CliError> {
+    let direction = match direction {
+        SeekDirection::Up => Direction::Up,
+        SeekDirection::Down => Direction::Down,
+    };
+    let frequency = radio.seek(direction, wrap, &mut Delay, SEEK_TIMEOUT_MS)?;
+    let signal = radio.get_signal_sample()?;
+    let value = json!({ "frequency": frequency, "rssi": signal.rssi, "stereo": signal.stereo });
+    print(json, value, || {
+        format!(
+            "found {} (RSSI {} dBµV, {})",
+            mhz(frequency),
+            signal.rssi,
+            if signal.stereo { "stereo" } else { "mono" }
+        )
+    });
+    Ok(())
+}
+
+fn scan(radio: &mut Radio, step: bool, rds_ms: u32, json: bool) -> Result<(), CliError> {
+    ensure_powered(radio)?;
+    let options = ScanOptions {
+        method: if step {
+            ScanMethod::Step
+        } else {
+            ScanMethod::Seek
+        },
+        rds_dwell_ms: rds_ms,
+        ..ScanOptions::default()
+    };
+    let stations = radio.scan_band::<_, MAX_STATIONS>(&mut Delay, &options)?;
+    let value: Vec<Value> = stations
+        .iter()
+        .map(|station| {
+            json!({
+                "frequency": station.frequency,
+                "rssi": station.rssi,
+                "stereo": station.stereo,
+                "pi": station.pi,
+                "ps": station.ps.as_ref().map(|ps| text(ps)),
+            })
+        })
+        .collect();
+    print(json, Value::Array(value), || {
+        let mut lines: Vec<String> = stations
+            .iter()
+            .map(|station| {
+                let mut line = format!(
+                    "{:>10}  RSSI {:>3}  {:<6}",
+                    mhz(station.frequency),
+                    station.rssi,
+                    if station.stereo { "stereo" } else { "mono" }
+                );
+                if let Some(pi) = station.pi {
+                    line += &format!("  {:04X}", pi);
+                }
+                if let Some(ps) = &station.ps {
+                    line += &format!("  {}", text(ps));
+                }
+                line
+            })
+            .collect();
+        lines.push(format!("{} stations", stations.len()));
+        lines.join("\n")
+    });
+    Ok(())
+}
+
+fn volume(radio: &mut Radio, level: Option<u8>, json: bool) -> Result<(), CliError> {
+    if let Some(level) = level {
+        radio.set_volume(level)?;
+    }
+    let volume = radio.get_volume()?.volume;
+    print(json, json!({ "volume": volume }), || {
+        format!("volume {}", volume)
+    });
+    Ok(())
+}
+
+fn mute(radio: &mut Radio, state: Option<Switch>, json: bool) -> Result<(), CliError> {
+    if let Some(state) = state {
+        radio.mute(matches!(state, Switch::On))?;
+    }
+    let muted = radio.snapshot()?.mute;
+    print(json, json!({ "mute": muted }), || {
+        if muted { "muted" } else { "not muted" }.to_string()
+    });
+    Ok(())
+}
+
+fn rds(radio: &mut Radio, duration: Option<Duration>, json: bool) -> Result<(), CliError> {
+    ensure_powered(radio)?;
+    let start = Instant::now();
+    let mut decoder = RdsDecoder::new();
+    let mut shown = (None, None, None, None);
+    while duration.is_none_or(|duration| start.elapsed() < duration) {
+        if let Some(group) = radio.read_corrected_rds_group()? {
+            decoder.decode_corrected(&group);
+        }
+        let current = (
+            decoder.pi(),
+            decoder.ps().map(|ps| text(ps)),
+            decoder.rt().map(text),
+            decoder.clock_time(),
+        );
+        if current != shown {
+            let value = json!({
+                "pi": current.0,
+                "ps": current.1,
+                "rt": current.2,
+                "ct": current.3.as_ref().map(clock_text),
+            });
+            print(json, value, || {
+                let mut parts = Vec::new();
+                if let Some(pi) = current.0 {
+                    parts.push(format!("PI {:04X}", pi));
+                }
+                if let Some(ps) = &current.1 {
+                    parts.push(format!("PS \"{}\"", ps));
+                }
+                if let Some(rt) = &current.2 {
+                    parts.push(format!("RT \"{}\"", rt));
+                }
+                if let Some(clock) = &current.3 {
+                    parts.push(format!("CT {}", clock_text(clock)));
+                }
+                parts.join("  ")
+            });
+            shown = current;
+        }
+        thread::sleep(RDS_POLL_INTERVAL);
+    }
+    Ok(())
+}
+
+fn field_text(field: Field) -> String {
+    match field {
+        Field::Flag(name) => name.to_string(),
+        Field::Value(name, value) => format!("{}={}", name, value),
+    }
+}
+
+fn dump_registers(radio: &mut Radio, json: bool) -> Result<(), CliError> {
+    let dump = RegisterDump::new(radio.read_all_registers()?);
+    let value: Vec<Value> = dump
+        .iter()
+        .map(|(register, value)| {
+            json!({
+                "register": register,
+                "name": register_name(register),
+                "value": value,
+                "fields": fields(register, value).map(field_text).collect::<Vec<_>>(),
+            })
+        })
+        .collect();
+    print(json, Value::Array(value), || {
+        dump.to_string().trim_end().to_string()
+    });
+    Ok(())
+}
+
+fn set_register(
+    radio: &mut Radio,
+    register: u8,
+    value: u16,
+    force: bool,
+    json: bool,
+) -> Result<(), CliError> {
+    if force {
+        radio.write_raw_forced(register, value)?;
+    } else {
+        radio.write_raw(register, value)?;
+    }
+    let read_back = radio.read_all_registers()?[register as usize];
+    let value = json!({ "register": register, "value": read_back });
+    print(json, value, || {
+        format!(
+            "0x{:02X} {} = 0x{:04X}",
+            register,
+            register_name(register),
+            read_back
+        )
+    });
+    Ok(())
+}
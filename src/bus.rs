@@ -0,0 +1,136 @@
+// Sharing the I²C bus with other devices.
+//
+// Any embedded-hal 0.2 bus handle works with `Rda5708m::new`. With the `eh1`
+// feature `Eh1I2c` adapts embedded-hal 1.0 buses, e.g. the shared bus devices
+// of embedded-hal-bus (`RefCellDevice`, `CriticalSectionDevice`,
+// `MutexDevice`) or embassy's blocking `I2cDevice`:
+//
+//     let bus = RefCell::new(i2c);
+//     let mut radio = Rda5708m::new(Eh1I2c::new(RefCellDevice::new(&bus)), Address::default());
+//     let mut rtc = Ds3231::new(RefCellDevice::new(&bus));
+//
+// Without shared bus handles the driver can hold no bus at all and borrow
+// one for each call:
+//
+//     let radio = Rda5708m::detached(Address::default());
+//     radio.with_i2c(&mut i2c).set_volume(5)?;
+
+use embedded_hal::blocking::i2c::{Read, Write, WriteRead};
+
+use crate::{Address, Rda5708m};
+
+/// Bus of a driver created with `Rda5708m::detached`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub struct NoBus;
+
+/// Bus borrowed for the calls on the driver returned by `Rda5708m::with_i2c`
+#[derive(Debug)]
+pub struct BorrowedI2c<'a, I2C>(&'a mut I2C);
+
+impl Rda5708m<NoBus> {
+    /// Driver without a bus, see `with_i2c`
+    pub fn detached<A: Into<Address>>(address: A) -> Self {
+        Rda5708m {
+            i2c: NoBus,
+            address: address.into().0,
+        }
+    }
+
+    /// Driver using `i2c` until it is dropped. The chip state lives in the
+    /// chip, so nothing is lost between calls with different borrows.
+    pub fn with_i2c<'a, I2C>(&self, i2c: &'a mut I2C) -> Rda5708m<BorrowedI2c<'a, I2C>> {
+        Rda5708m {
+            i2c: BorrowedI2c(i2c),
+            address: self.address,
+        }
+    }
+}
+
+impl<I2C: Write> Write for BorrowedI2c<'_, I2C> {
+    type Error = I2C::Error;
+
+    fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.0.write(address, bytes)
+    }
+}
+
+impl<I2C: Read> Read for BorrowedI2c<'_, I2C> {
+    type Error = I2C::Error;
+
+    fn read(&mut self, address: u8, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.0.read(address, buffer)
+    }
+}
+
+impl<I2C: WriteRead> WriteRead for BorrowedI2c<'_, I2C> {
+    type Error = I2C::Error;
+
+    fn write_read(
+        &mut self,
+        address: u8,
+        bytes: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.0.write_read(address, bytes, buffer)
+    }
+}
+
+/// embedded-hal 1.0 `I2c` bus used through the 0.2 traits of the driver
+#[cfg(feature = "eh1")]
+#[derive(Debug)]
+pub struct Eh1I2c<I2C> {
+    i2c: I2C,
+}
+
+#[cfg(feature = "eh1")]
+impl<I2C> Eh1I2c<I2C> {
+    pub fn new(i2c: I2C) -> Self {
+        Eh1I2c { i2c }
+    }
+
+    pub fn inner(&self) -> &I2C {
+        &self.i2c
+    }
+
+    pub fn inner_mut(&mut self) -> &mut I2C {
+        &mut self.i2c
+    }
+
+    pub fn into_inner(self) -> I2C {
+        self.i2c
+    }
+}
+
+#[cfg(feature = "eh1")]
+impl<I2C: embedded_hal_1::i2c::I2c> Write for Eh1I2c<I2C> {
+    type Error = I2C::Error;
+
+    fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), Self::Error> {
+        embedded_hal_1::i2c::I2c::write(&mut self.i2c, address, bytes)
+    }
+}
+
+#[cfg(feature = "eh1")]
+impl<I2C: embedded_hal_1::i2c::I2c> Read for Eh1I2c<I2C> {
+    type Error = I2C::Error;
+
+    fn read(&mut self, address: u8, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        embedded_hal_1::i2c::I2c::read(&mut self.i2c, address, buffer)
+    }
+}
+
+#[cfg(feature = "eh1")]
+impl<I2C: embedded_hal_1::i2c::I2c> WriteRead for Eh1I2c<I2C> {
+    type Error = I2C::Error;
+
+    fn write_read(
+        &mut self,
+        address: u8,
+        bytes: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        embedded_hal_1::i2c::I2c::write_read(&mut self.i2c, address, bytes, buffer)
+    }
+}
@@ -1,24 +1,105 @@
 #![no_std]
 
+#[cfg(feature = "std")]
+extern crate std;
+
+use core::fmt;
+
+use crate::band::{channel_to_frequency, frequency_to_channel, is_tunable, step_channel};
+use crate::rds::sync::CorrectedGroup;
+use crate::rds::Group;
 use crate::register_address::{
     ConfigBitFlags, Register, RssiBitFlag, StatusRegister, TuningBitFlag, VolumeBitFlag,
     VolumeRegister,
 };
+use crate::trace::REGISTER_COUNT;
 
+pub mod band;
+pub mod bus;
+pub mod presets;
+pub mod rds;
+mod rds_fifo;
 pub mod register_address;
+mod scan;
+pub mod signal;
+#[cfg(feature = "sim")]
+pub mod sim;
+mod state;
+#[cfg(feature = "ufmt")]
+mod text;
+pub mod trace;
+pub mod traffic;
+
+pub use crate::band::{Band, Direction, Spacing};
+#[cfg(feature = "eh1")]
+pub use crate::bus::Eh1I2c;
+pub use crate::bus::{BorrowedI2c, NoBus};
+pub use crate::presets::{Preset, Presets};
+pub use crate::rds_fifo::{RdsFifoGroup, RdsMode};
+pub use crate::scan::{ScanMethod, ScanOptions, Station};
+pub use crate::signal::{SignalMonitor, SignalState};
+pub use crate::state::RadioState;
+pub use crate::trace::{RegisterDump, Traced};
+pub use crate::traffic::{TrafficAnnouncer, TrafficEvent};
 
 #[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
 pub enum Error<E> {
-    // I²C bus error
+    /// I²C bus error
     I2C(E),
+    /// `write_raw` to a read only or nonexistent register
+    ReadOnlyRegister(u8),
+    /// An argument outside its valid range, described by the text
+    InvalidArgument(&'static str),
+    /// Frequency in kHz outside the selected band
+    FrequencyOutOfBand(u32),
+    /// A tune or seek did not complete in time
+    Timeout,
+    /// A seek ended without finding a station
+    SeekFailed,
+    /// The chip id register did not read 0x5804
+    WrongChipId(u16),
+    /// The chip has to be enabled with `start` first
+    NotPowered,
 }
 
+impl<E: fmt::Debug> fmt::Display for Error<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::I2C(e) => write!(f, "I²C bus error: {:?}", e),
+            Error::ReadOnlyRegister(register) => {
+                write!(f, "register 0x{:02x} is read only", register)
+            }
+            Error::InvalidArgument(message) => write!(f, "invalid argument: {}", message),
+            Error::FrequencyOutOfBand(freq) => {
+                write!(f, "{} kHz is outside the selected band", freq)
+            }
+            Error::Timeout => f.write_str("tune or seek did not complete in time"),
+            Error::SeekFailed => f.write_str("seek found no station"),
+            Error::WrongChipId(id) => write!(
+                f,
+                "wrong chip id 0x{:04x}, expected 0x{:04x}",
+                id, DEVICE_ID
+            ),
+            Error::NotPowered => f.write_str("the chip is not powered up"),
+        }
+    }
+}
+
+impl<E: fmt::Debug> core::error::Error for Error<E> {}
+
 // 顺序模式地址
 const DEVICE_BASE_SEQ_ADDRESS: u8 = 0b10001;
 // 随机模式地址
 const DEVICE_BASE_RANDOM_ADDRESS: u8 = 0b10001;
 // 设备ID
 const DEVICE_ID: u16 = 0x5804;
+// VOLUME and SEEK_TH are 4 bit fields
+pub(crate) const MAX_VOLUME: u8 = 15;
+const MAX_SEEK_THRESHOLD: u8 = 15;
+// BLERA/BLERB: 6 or more errors, not corrected
+pub(crate) const BLOCK_ERROR_UNCORRECTABLE: u8 = 0b11;
 
 #[derive(Debug)]
 pub struct Rda5708m<I2C> {
@@ -81,18 +162,56 @@ where
         Ok(id == DEVICE_ID)
     }
 
+    /// Like `check_id`, failing with `Error::WrongChipId` for other chips
+    pub fn verify_id(&mut self) -> Result<(), Error<E>> {
+        let id = self.read_register(Register::RDA5807M_REG_CHIPID)?;
+        if id != DEVICE_ID {
+            return Err(Error::WrongChipId(id));
+        }
+        Ok(())
+    }
+
+    /// Read registers 0x00 to 0x0F, e.g. for a `RegisterDump`
+    pub fn read_all_registers(&mut self) -> Result<[u16; REGISTER_COUNT], Error<E>> {
+        let mut registers = [0; REGISTER_COUNT];
+        for (register, value) in (0..).zip(registers.iter_mut()) {
+            *value = self.read_register(register)?;
+        }
+        Ok(registers)
+    }
+
+    /// Write a register directly. The chip id (0x00), the status and RDS
+    /// registers (0x0A to 0x0F) and registers past 0x0F are refused with
+    /// `Error::ReadOnlyRegister`, see `write_raw_forced`.
+    pub fn write_raw(&mut self, register: u8, value: u16) -> Result<(), Error<E>> {
+        if register == Register::RDA5807M_REG_CHIPID || register >= Register::RDA5807M_REG_STATUS {
+            return Err(Error::ReadOnlyRegister(register));
+        }
+        self.write_register(register, value)
+    }
+
+    /// Write any register directly, without the checks of `write_raw`
+    pub fn write_raw_forced(&mut self, register: u8, value: u16) -> Result<(), Error<E>> {
+        self.write_register(register, value)
+    }
+
     // Start the device
     pub fn start(&mut self) -> Result<(), Error<E>> {
         let config = ConfigBitFlags::DHIZ
             | ConfigBitFlags::DMUTE
             | ConfigBitFlags::BASS
             | ConfigBitFlags::SEEKUP
+            | ConfigBitFlags::SEEK
             | ConfigBitFlags::RDS
             | ConfigBitFlags::NEW
             | ConfigBitFlags::ENABLE;
         let tuning = TuningBitFlag::BAND_87_108_MHZ | TuningBitFlag::SPACE_100_KHZ;
         self.write_register(Register::RDA5807M_REG_CONFIG, config)?;
-        self.write_register(Register::RDA5807M_REG_TUNING, tuning)
+        self.update_register(
+            Register::RDA5807M_REG_TUNING,
+            TuningBitFlag::BAND_MASK | TuningBitFlag::SPACE_MASK,
+            tuning,
+        )
     }
 
     // Stop the device
@@ -105,11 +224,10 @@ where
         self.i2c
     }
 
-    // set device volume
+    // set device volume, 0 - 15
     pub fn set_volume(&mut self, volume: u8) -> Result<(), Error<E>> {
-        let mut volume = volume;
-        if volume > 15 {
-            volume = 15;
+        if volume > MAX_VOLUME {
+            return Err(Error::InvalidArgument("volume above 15"));
         }
         self.update_register(
             Register::RDA5807M_REG_VOLUME,
@@ -128,13 +246,17 @@ where
         self.update_register(
             Register::RDA5807M_REG_CONFIG,
             ConfigBitFlags::DMUTE,
-            if mute { ConfigBitFlags::DMUTE } else { 0 },
+            // DMUTE: 0 = mute, 1 = normal
+            if mute { 0 } else { ConfigBitFlags::DMUTE },
         )
     }
 
     /// 自动搜台信号阈值强度默认为8
     /// 数值越低搜到的台越多
     pub fn set_seek_threshold(&mut self, threshold: u8) -> Result<(), Error<E>> {
+        if threshold > MAX_SEEK_THRESHOLD {
+            return Err(Error::InvalidArgument("seek threshold above 15"));
+        }
         self.update_register(
             Register::RDA5807M_REG_VOLUME,
             VolumeBitFlag::SEEK_TH_MASK,
@@ -147,10 +269,8 @@ where
         let config = self.read_register(Register::RDA5807M_REG_VOLUME)?;
         let volume = config & VolumeBitFlag::VOLUME_MASK;
         let mut volume = volume + 1;
-        if !wrap {
-            if volume > 15 {
-                volume = 15;
-            }
+        if !wrap && volume > 15 {
+            volume = 15;
         }
         self.update_register_by_old(
             Register::RDA5807M_REG_VOLUME,
@@ -164,11 +284,9 @@ where
     pub fn volume_down(&mut self, wrap: bool) -> Result<(), Error<E>> {
         let config = self.read_register(Register::RDA5807M_REG_VOLUME)?;
         let volume = config & VolumeBitFlag::VOLUME_MASK;
-        let mut volume = volume - 1;
-        if !wrap {
-            if volume > 15 {
-                volume = 0;
-            }
+        let mut volume = volume.wrapping_sub(1) & VolumeBitFlag::VOLUME_MASK;
+        if !wrap && volume == VolumeBitFlag::VOLUME_MASK {
+            volume = 0;
         }
         self.update_register_by_old(
             Register::RDA5807M_REG_VOLUME,
@@ -178,21 +296,30 @@ where
         )
     }
 
+    /// Start a seek up, `Error::NotPowered` if the chip is not enabled
     pub fn seek_up(&mut self, wrap: bool) -> Result<(), Error<E>> {
-        self.update_register(
-            Register::RDA5807M_REG_CONFIG,
-            ConfigBitFlags::SEEKUP | ConfigBitFlags::SEEK | ConfigBitFlags::SKMODE,
-            ConfigBitFlags::SEEKUP
-                | ConfigBitFlags::SEEK
-                | if wrap { 0 } else { ConfigBitFlags::SKMODE },
-        )
+        self.start_seek(Direction::Up, wrap)
     }
 
+    /// Start a seek down, `Error::NotPowered` if the chip is not enabled
     pub fn seek_down(&mut self, wrap: bool) -> Result<(), Error<E>> {
-        self.update_register(
+        self.start_seek(Direction::Down, wrap)
+    }
+
+    fn start_seek(&mut self, direction: Direction, wrap: bool) -> Result<(), Error<E>> {
+        let config = self.read_register(Register::RDA5807M_REG_CONFIG)?;
+        if config & ConfigBitFlags::ENABLE == 0 {
+            return Err(Error::NotPowered);
+        }
+        let seekup = match direction {
+            Direction::Up => ConfigBitFlags::SEEKUP,
+            Direction::Down => 0,
+        };
+        self.update_register_by_old(
             Register::RDA5807M_REG_CONFIG,
+            config,
             ConfigBitFlags::SEEKUP | ConfigBitFlags::SEEK | ConfigBitFlags::SKMODE,
-            0 | ConfigBitFlags::SEEK | if wrap { 0 } else { ConfigBitFlags::SKMODE },
+            seekup | ConfigBitFlags::SEEK | if wrap { 0 } else { ConfigBitFlags::SKMODE },
         )
     }
 
@@ -208,43 +335,55 @@ where
         Ok((band as u8, spacing as u8, config))
     }
 
+    pub fn set_band_and_spacing(&mut self, band: Band, spacing: Spacing) -> Result<(), Error<E>> {
+        self.update_register(
+            Register::RDA5807M_REG_TUNING,
+            TuningBitFlag::BAND_MASK | TuningBitFlag::SPACE_MASK,
+            band.register_value() | spacing.register_value(),
+        )
+    }
+
     pub fn get_frequency(&mut self) -> Result<u32, Error<E>> {
         let (band, spacing, _) = self.get_band_and_spacing()?;
-        let spacing: u32 = match spacing {
-            0b00 => 100,
-            0b01 => 200,
-            0b10 => 50,
-            0b11 => 25,
-            _ => 0,
-        };
-        let chan = self.get_status()?.readchan as u32;
-        let freq = match band {
-            0b00 => 87_000 + spacing * chan,
-            0b01 | 0b10 => 76_000 + spacing * chan,
-            0b11 => 65_000 + spacing * chan,
-            _ => 0,
-        };
-        Ok(freq)
+        let chan = self.get_status()?.readchan;
+        Ok(channel_to_frequency(
+            Band::from_bits(band),
+            Spacing::from_bits(spacing),
+            chan,
+        ))
     }
 
+    /// Tune `freq` in kHz, `Error::FrequencyOutOfBand` outside the selected band
+    /// or beyond its last channel
     pub fn set_frequency(&mut self, freq: u32) -> Result<(), Error<E>> {
         let (band, spacing, config) = self.get_band_and_spacing()?;
-        let spacing: u32 = match spacing {
-            0b00 => 100,
-            0b01 => 200,
-            0b10 => 50,
-            0b11 => 25,
-            _ => 0,
-        };
-        let chan = match band {
-            0b00 => (freq - 87_000) / spacing,
-            0b01 | 0b10 => (freq - 76_000) / spacing,
-            0b11 => (freq - 65_000) / spacing,
-            _ => 0,
-        };
+        let (band, spacing) = (Band::from_bits(band), Spacing::from_bits(spacing));
+        if !is_tunable(band, spacing, freq) {
+            return Err(Error::FrequencyOutOfBand(freq));
+        }
+        let chan = frequency_to_channel(band, spacing, freq);
+        self.set_channel_by_old(config, chan)
+    }
 
+    /// Tune `steps` channels up or down from the current one, wrapping around or
+    /// stopping at the band edges. Returns the new frequency in kHz.
+    pub fn tune_step(
+        &mut self,
+        direction: Direction,
+        steps: u16,
+        wrap: bool,
+    ) -> Result<u32, Error<E>> {
+        let (band, spacing, config) = self.get_band_and_spacing()?;
+        let (band, spacing) = (Band::from_bits(band), Spacing::from_bits(spacing));
+        let chan = self.get_status()?.readchan;
+        let chan = step_channel(band, spacing, chan, direction, steps, wrap);
+        self.set_channel_by_old(config, chan)?;
+        Ok(channel_to_frequency(band, spacing, chan))
+    }
+
+    fn set_channel_by_old(&mut self, config: u16, chan: u16) -> Result<(), Error<E>> {
         let mask = TuningBitFlag::CHAN_MASK | TuningBitFlag::TUNE;
-        let new_mask_value = ((chan as u16) << TuningBitFlag::CHAN_SHIFT) | TuningBitFlag::TUNE;
+        let new_mask_value = (chan << TuningBitFlag::CHAN_SHIFT) | TuningBitFlag::TUNE;
         self.update_register_by_old(Register::RDA5807M_REG_TUNING, config, mask, new_mask_value)
     }
 
@@ -269,10 +408,39 @@ where
 
         Ok((blera as u8, blerb as u8))
     }
+
+    /// Read the pending RDS group, if the chip has one ready and blocks A and B
+    /// were received with correctable errors only
+    pub fn read_rds_group(&mut self) -> Result<Option<Group>, Error<E>> {
+        if !self.get_status()?.rdsr {
+            return Ok(None);
+        }
+        let (blera, blerb) = self.get_rds_block_errors()?;
+        if blera == BLOCK_ERROR_UNCORRECTABLE || blerb == BLOCK_ERROR_UNCORRECTABLE {
+            return Ok(None);
+        }
+        self.get_rds_blocks().map(Some)
+    }
+
+    /// Read the pending RDS group with the chip's error indications, for
+    /// `RdsDecoder::decode_corrected`. Unlike `read_rds_group` groups with
+    /// errors in block A are kept.
+    pub fn read_corrected_rds_group(&mut self) -> Result<Option<CorrectedGroup>, Error<E>> {
+        if !self.get_status()?.rdsr {
+            return Ok(None);
+        }
+        let (blera, blerb) = self.get_rds_block_errors()?;
+        let blocks = self.get_rds_blocks()?;
+        Ok(Some(CorrectedGroup::from_block_errors(
+            blocks, blera, blerb,
+        )))
+    }
 }
 
 // I2C device address
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
 pub struct Address(pub(crate) u8);
 
 impl Default for Address {
@@ -292,3 +460,49 @@ impl Address {
         Address(DEVICE_BASE_SEQ_ADDRESS)
     }
 }
+
+// the bus itself is left out, it rarely implements the formatting traits
+#[cfg(feature = "defmt")]
+impl<I2C> defmt::Format for Rda5708m<I2C> {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "Rda5708m {{ address: {=u8:#04x} }}", self.address);
+    }
+}
+
+#[cfg(feature = "ufmt")]
+impl<E: ufmt::uDebug> ufmt::uDebug for Error<E> {
+    fn fmt<W>(&self, f: &mut ufmt::Formatter<'_, W>) -> Result<(), W::Error>
+    where
+        W: ufmt::uWrite + ?Sized,
+    {
+        match self {
+            Error::I2C(e) => f.debug_tuple("I2C")?.field(e)?.finish(),
+            Error::ReadOnlyRegister(register) => {
+                f.debug_tuple("ReadOnlyRegister")?.field(register)?.finish()
+            }
+            Error::InvalidArgument(message) => f
+                .debug_tuple("InvalidArgument")?
+                .field(&text::Text::from(*message))?
+                .finish(),
+            Error::FrequencyOutOfBand(freq) => {
+                f.debug_tuple("FrequencyOutOfBand")?.field(freq)?.finish()
+            }
+            Error::Timeout => f.write_str("Timeout"),
+            Error::SeekFailed => f.write_str("SeekFailed"),
+            Error::WrongChipId(id) => f.debug_tuple("WrongChipId")?.field(id)?.finish(),
+            Error::NotPowered => f.write_str("NotPowered"),
+        }
+    }
+}
+
+#[cfg(feature = "ufmt")]
+impl<I2C> ufmt::uDebug for Rda5708m<I2C> {
+    fn fmt<W>(&self, f: &mut ufmt::Formatter<'_, W>) -> Result<(), W::Error>
+    where
+        W: ufmt::uWrite + ?Sized,
+    {
+        f.debug_struct("Rda5708m")?
+            .field("address", &self.address)?
+            .finish()
+    }
+}
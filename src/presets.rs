@@ -0,0 +1,348 @@
+use crate::band::{frequency_to_channel, is_tunable, Band, Spacing};
+use crate::register_address::{Register, TuningBitFlag};
+use crate::{Error, Rda5708m, MAX_VOLUME};
+
+// Every record (header and presets) is 16 bytes so it can be written to
+// NOR flash with any write granularity up to 16 bytes.
+pub const RECORD_SIZE: usize = 16;
+pub const PRESET_NAME_LEN: usize = 8;
+
+const MAGIC: [u8; 4] = *b"RDA5";
+const VERSION: u8 = 1;
+// Erased flash reads back as 0xFF, so an explicit marker is used for used slots
+const SLOT_USED: u8 = 0x5A;
+const SLOT_EMPTY: u8 = 0x00;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum PresetError<S> {
+    // Error of the underlying storage
+    Storage(S),
+    // No preset data found (blank or foreign storage)
+    BadMagic,
+    UnsupportedVersion(u8),
+    // Stored with a different number of slots
+    CapacityMismatch(u8),
+    // A record failed its checksum
+    Checksum,
+}
+
+impl<S> From<S> for PresetError<S> {
+    fn from(value: S) -> Self {
+        PresetError::Storage(value)
+    }
+}
+
+/// Non-volatile medium presets are saved to.
+/// Offsets are relative to the start of the preset area.
+pub trait PresetStorage {
+    type Error;
+
+    /// Prepare `len` bytes for writing, e.g. erase flash sectors
+    fn erase(&mut self, len: usize) -> Result<(), Self::Error>;
+
+    fn read(&mut self, offset: usize, buf: &mut [u8]) -> Result<(), Self::Error>;
+
+    fn write(&mut self, offset: usize, data: &[u8]) -> Result<(), Self::Error>;
+}
+
+/// Storage slice too small for the presets
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub struct OutOfBounds;
+
+// Any byte buffer, e.g. a RAM mirror or EEPROM image
+impl PresetStorage for [u8] {
+    type Error = OutOfBounds;
+
+    fn erase(&mut self, len: usize) -> Result<(), Self::Error> {
+        self.get_mut(..len).ok_or(OutOfBounds)?.fill(0xff);
+        Ok(())
+    }
+
+    fn read(&mut self, offset: usize, buf: &mut [u8]) -> Result<(), Self::Error> {
+        let src = self.get(offset..offset + buf.len()).ok_or(OutOfBounds)?;
+        buf.copy_from_slice(src);
+        Ok(())
+    }
+
+    fn write(&mut self, offset: usize, data: &[u8]) -> Result<(), Self::Error> {
+        self.get_mut(offset..offset + data.len())
+            .ok_or(OutOfBounds)?
+            .copy_from_slice(data);
+        Ok(())
+    }
+}
+
+/// `PresetStorage` on an `embedded-storage` NOR flash region starting at
+/// `offset`, which must be aligned to the flash erase size.
+#[cfg(feature = "embedded-storage")]
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub struct NorFlashStorage<F> {
+    flash: F,
+    offset: u32,
+}
+
+#[cfg(feature = "embedded-storage")]
+impl<F> NorFlashStorage<F> {
+    pub fn new(flash: F, offset: u32) -> Self {
+        NorFlashStorage { flash, offset }
+    }
+
+    pub fn destroy(self) -> F {
+        self.flash
+    }
+}
+
+#[cfg(feature = "embedded-storage")]
+impl<F: embedded_storage::nor_flash::NorFlash> PresetStorage for NorFlashStorage<F> {
+    type Error = F::Error;
+
+    fn erase(&mut self, len: usize) -> Result<(), Self::Error> {
+        let len = (len as u32).div_ceil(F::ERASE_SIZE as u32) * F::ERASE_SIZE as u32;
+        self.flash.erase(self.offset, self.offset + len)
+    }
+
+    fn read(&mut self, offset: usize, buf: &mut [u8]) -> Result<(), Self::Error> {
+        self.flash.read(self.offset + offset as u32, buf)
+    }
+
+    fn write(&mut self, offset: usize, data: &[u8]) -> Result<(), Self::Error> {
+        self.flash.write(self.offset + offset as u32, data)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub struct Preset {
+    // kHz
+    pub frequency: u32,
+    pub band: Band,
+    pub spacing: Spacing,
+    // Display name, e.g. the station's PS
+    pub name: [u8; PRESET_NAME_LEN],
+    // 0..=15
+    pub volume: u8,
+}
+
+impl Preset {
+    /// Layout: marker, frequency (u24 LE), band << 2 | spacing, volume, name, reserved, checksum
+    pub fn to_bytes(&self) -> [u8; RECORD_SIZE] {
+        let mut record = [0; RECORD_SIZE];
+        record[0] = SLOT_USED;
+        record[1..4].copy_from_slice(&self.frequency.to_le_bytes()[..3]);
+        record[4] = (self.band.bits() << 2) | self.spacing.bits();
+        record[5] = self.volume.min(15);
+        record[6..14].copy_from_slice(&self.name);
+        seal(&mut record);
+        record
+    }
+
+    /// Decode a record, `Ok(None)` for an empty slot
+    pub fn from_bytes(record: &[u8; RECORD_SIZE]) -> Result<Option<Self>, PresetError<()>> {
+        check(record)?;
+        if record[0] != SLOT_USED {
+            return Ok(None);
+        }
+        let mut name = [0; PRESET_NAME_LEN];
+        name.copy_from_slice(&record[6..14]);
+        Ok(Some(Preset {
+            frequency: u32::from_le_bytes([record[1], record[2], record[3], 0]),
+            band: Band::from_bits(record[4] >> 2),
+            spacing: Spacing::from_bits(record[4]),
+            name,
+            volume: record[5] & 0x0f,
+        }))
+    }
+}
+
+/// `N` preset slots, at most 255 to be saved or loaded
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Presets<const N: usize> {
+    slots: [Option<Preset>; N],
+}
+
+impl<const N: usize> Default for Presets<N> {
+    fn default() -> Self {
+        Presets { slots: [None; N] }
+    }
+}
+
+#[cfg(feature = "ufmt")]
+impl<const N: usize> ufmt::uDebug for Presets<N> {
+    fn fmt<W>(&self, f: &mut ufmt::Formatter<'_, W>) -> Result<(), W::Error>
+    where
+        W: ufmt::uWrite + ?Sized,
+    {
+        f.debug_struct("Presets")?
+            .field("slots", &self.slots.as_slice())?
+            .finish()
+    }
+}
+
+impl<const N: usize> Presets<N> {
+    /// Bytes taken in storage: header plus one record per slot
+    pub const STORAGE_SIZE: usize = RECORD_SIZE * (N + 1);
+
+    // the header stores the capacity in one byte, larger `N` fail to compile
+    // once `save` or `load` is used
+    const STORED_CAPACITY: u8 = {
+        assert!(
+            N <= u8::MAX as usize,
+            "presets can be stored for at most 255 slots"
+        );
+        N as u8
+    };
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    pub fn get(&self, idx: usize) -> Option<&Preset> {
+        self.slots.get(idx)?.as_ref()
+    }
+
+    /// Store `preset` in slot `idx`, returns `false` if `idx` is out of range
+    pub fn set(&mut self, idx: usize, preset: Preset) -> bool {
+        match self.slots.get_mut(idx) {
+            Some(slot) => {
+                *slot = Some(preset);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn remove(&mut self, idx: usize) -> Option<Preset> {
+        self.slots.get_mut(idx)?.take()
+    }
+
+    /// Used slots with their index
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &Preset)> {
+        self.slots
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, slot)| slot.as_ref().map(|preset| (idx, preset)))
+    }
+
+    pub fn load<S: PresetStorage + ?Sized>(storage: &mut S) -> Result<Self, PresetError<S::Error>> {
+        let mut record = [0; RECORD_SIZE];
+        storage.read(0, &mut record)?;
+        if record[..4] != MAGIC {
+            return Err(PresetError::BadMagic);
+        }
+        check(&record).map_err(|_| PresetError::Checksum)?;
+        if record[4] != VERSION {
+            return Err(PresetError::UnsupportedVersion(record[4]));
+        }
+        if record[5] != Self::STORED_CAPACITY {
+            return Err(PresetError::CapacityMismatch(record[5]));
+        }
+
+        let mut presets = Self::new();
+        for (idx, slot) in presets.slots.iter_mut().enumerate() {
+            storage.read(RECORD_SIZE * (idx + 1), &mut record)?;
+            *slot = Preset::from_bytes(&record).map_err(|_| PresetError::Checksum)?;
+        }
+        Ok(presets)
+    }
+
+    pub fn save<S: PresetStorage + ?Sized>(
+        &self,
+        storage: &mut S,
+    ) -> Result<(), PresetError<S::Error>> {
+        storage.erase(Self::STORAGE_SIZE)?;
+
+        let mut header = [0; RECORD_SIZE];
+        header[..4].copy_from_slice(&MAGIC);
+        header[4] = VERSION;
+        header[5] = Self::STORED_CAPACITY;
+        seal(&mut header);
+        storage.write(0, &header)?;
+
+        for (idx, slot) in self.slots.iter().enumerate() {
+            let record = match slot {
+                Some(preset) => preset.to_bytes(),
+                None => {
+                    let mut empty = [0; RECORD_SIZE];
+                    empty[0] = SLOT_EMPTY;
+                    seal(&mut empty);
+                    empty
+                }
+            };
+            storage.write(RECORD_SIZE * (idx + 1), &record)?;
+        }
+        Ok(())
+    }
+}
+
+fn checksum(record: &[u8; RECORD_SIZE]) -> u8 {
+    !record[..RECORD_SIZE - 1]
+        .iter()
+        .fold(0u8, |sum, byte| sum.wrapping_add(*byte))
+}
+
+fn seal(record: &mut [u8; RECORD_SIZE]) {
+    record[RECORD_SIZE - 1] = checksum(record);
+}
+
+fn check(record: &[u8; RECORD_SIZE]) -> Result<(), PresetError<()>> {
+    if record[RECORD_SIZE - 1] == checksum(record) {
+        Ok(())
+    } else {
+        Err(PresetError::Checksum)
+    }
+}
+
+impl<I2C, E> Rda5708m<I2C>
+where
+    I2C: embedded_hal::blocking::i2c::Write<Error = E>
+        + embedded_hal::blocking::i2c::Read<Error = E>
+        + embedded_hal::blocking::i2c::WriteRead<Error = E>,
+{
+    /// Switch to the preset's band and spacing, tune its frequency and set its volume.
+    /// Nothing is changed if the frequency is outside the preset's band or
+    /// beyond its last channel, or the volume above 15.
+    pub fn apply_preset(&mut self, preset: &Preset) -> Result<(), Error<E>> {
+        if !is_tunable(preset.band, preset.spacing, preset.frequency) {
+            return Err(Error::FrequencyOutOfBand(preset.frequency));
+        }
+        if preset.volume > MAX_VOLUME {
+            return Err(Error::InvalidArgument("volume above 15"));
+        }
+        let config = self.read_register(Register::RDA5807M_REG_TUNING)?;
+        let chan = frequency_to_channel(preset.band, preset.spacing, preset.frequency);
+        let mask = TuningBitFlag::CHAN_MASK
+            | TuningBitFlag::TUNE
+            | TuningBitFlag::BAND_MASK
+            | TuningBitFlag::SPACE_MASK;
+        let new_mask_value = (chan << TuningBitFlag::CHAN_SHIFT)
+            | TuningBitFlag::TUNE
+            | preset.band.register_value()
+            | preset.spacing.register_value();
+        self.update_register_by_old(Register::RDA5807M_REG_TUNING, config, mask, new_mask_value)?;
+        self.set_volume(preset.volume)
+    }
+
+    /// Apply preset `idx`, returns `false` if that slot is empty
+    pub fn recall_preset<const N: usize>(
+        &mut self,
+        presets: &Presets<N>,
+        idx: usize,
+    ) -> Result<bool, Error<E>> {
+        match presets.get(idx) {
+            Some(preset) => self.apply_preset(preset).map(|_| true),
+            None => Ok(false),
+        }
+    }
+}
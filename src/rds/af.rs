@@ -0,0 +1,92 @@
+// Alternative Frequency codes (IEC 62106 table 10), FM band II only
+
+use heapless::Vec;
+
+// Code 205 is a filler, 224-249 announce the number of AFs following
+pub const FILLER: u8 = 205;
+pub const COUNT_BASE: u8 = 224;
+// The next code is an LF/MF frequency
+pub const LF_MF_FOLLOWS: u8 = 250;
+// at most 25 AFs per list
+pub const MAX_AF: usize = 25;
+
+/// Frequency in kHz of an AF code, `None` for special codes
+pub fn af_frequency(code: u8) -> Option<u32> {
+    match code {
+        1..=204 => Some(87_500 + code as u32 * 100),
+        _ => None,
+    }
+}
+
+/// AF code of a frequency in kHz, `None` outside 87.6 - 107.9MHz or off the 100kHz grid
+pub fn af_code(freq: u32) -> Option<u8> {
+    if !(87_600..=107_900).contains(&freq) || !freq.is_multiple_of(100) {
+        return None;
+    }
+    Some(((freq - 87_500) / 100) as u8)
+}
+
+/// Number of AFs announced by a count code
+pub fn af_count(code: u8) -> Option<u8> {
+    match code {
+        COUNT_BASE..=249 => Some(code - COUNT_BASE),
+        _ => None,
+    }
+}
+
+/// AF list sent with method A in block C of group 0A: a count code with the
+/// first frequency, then pairs of frequencies
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct AfList {
+    count: Option<u8>,
+    frequencies: Vec<u32, MAX_AF>,
+}
+
+#[cfg(feature = "ufmt")]
+impl ufmt::uDebug for AfList {
+    fn fmt<W>(&self, f: &mut ufmt::Formatter<'_, W>) -> Result<(), W::Error>
+    where
+        W: ufmt::uWrite + ?Sized,
+    {
+        f.debug_struct("AfList")?
+            .field("count", &self.count)?
+            .field("frequencies", &self.frequencies.as_slice())?
+            .finish()
+    }
+}
+
+impl AfList {
+    /// Decode block C of group 0A
+    pub(crate) fn decode(&mut self, c: u16) {
+        let [first, second] = c.to_be_bytes();
+        // LF/MF frequencies are not supported by the chip
+        if first == LF_MF_FOLLOWS {
+            return;
+        }
+        for code in [first, second] {
+            if let Some(count) = af_count(code) {
+                // a new list starts when the count changes
+                if self.count != Some(count) {
+                    self.frequencies.clear();
+                }
+                self.count = Some(count);
+            } else if let Some(freq) = af_frequency(code) {
+                if !self.frequencies.contains(&freq) {
+                    let _ = self.frequencies.push(freq);
+                }
+            }
+        }
+    }
+
+    /// Frequencies in kHz received so far
+    pub fn frequencies(&self) -> &[u32] {
+        &self.frequencies
+    }
+
+    /// `true` once as many frequencies as announced were received
+    pub fn is_complete(&self) -> bool {
+        self.count
+            .is_some_and(|count| self.frequencies.len() >= count as usize)
+    }
+}
@@ -0,0 +1,99 @@
+// RDS character set (EBU Latin based repertoire, IEC 62106 Annex E, table E.1).
+// PS, RadioText and PTYN bytes use this table instead of ASCII.
+
+use heapless::String;
+
+// 0x80 - 0xFF
+const UPPER: [char; 128] = [
+    // 0x80
+    'á', 'à', 'é', 'è', 'í', 'ì', 'ó', 'ò', 'ú', 'ù', 'Ñ', 'Ç', 'Ş', 'ß', '¡', 'Ĳ',
+    // 0x90
+    'â', 'ä', 'ê', 'ë', 'î', 'ï', 'ô', 'ö', 'û', 'ü', 'ñ', 'ç', 'ş', 'ğ', 'ı', 'ĳ',
+    // 0xA0
+    'ª', 'α', '©', '‰', 'Ğ', 'ě', 'ň', 'ő', 'π', '€', '£', '$', '←', '↑', '→', '↓',
+    // 0xB0
+    'º', '¹', '²', '³', '±', 'İ', 'ń', 'ű', 'µ', '¿', '÷', '°', '¼', '½', '¾', '§',
+    // 0xC0
+    'Á', 'À', 'É', 'È', 'Í', 'Ì', 'Ó', 'Ò', 'Ú', 'Ù', 'Ř', 'Č', 'Š', 'Ž', 'Ð', 'Ŀ',
+    // 0xD0
+    'Â', 'Ä', 'Ê', 'Ë', 'Î', 'Ï', 'Ô', 'Ö', 'Û', 'Ü', 'ř', 'č', 'š', 'ž', 'đ', 'ŀ',
+    // 0xE0
+    'Ã', 'Å', 'Æ', 'Œ', 'ŷ', 'Ý', 'Õ', 'Ø', 'Þ', 'Ŋ', 'Ŕ', 'Ć', 'Ś', 'Ź', 'Ŧ', 'ð',
+    // 0xF0, 0xFF is not assigned
+    'ã', 'å', 'æ', 'œ', 'ŵ', 'ý', 'õ', 'ø', 'þ', 'ŋ', 'ŕ', 'ć', 'ś', 'ź', 'ŧ', ' ',
+];
+
+// Control codes used in RadioText
+pub const LINE_FEED: u8 = 0x0a;
+pub const END_OF_HEADLINE: u8 = 0x0b;
+pub const END_OF_TEXT: u8 = 0x0d;
+pub const SOFT_HYPHEN: u8 = 0x1f;
+
+/// Character for one RDS byte. Control codes below 0x20 map to the
+/// matching ASCII control character, unassigned codes to a space.
+pub fn to_char(byte: u8) -> char {
+    match byte {
+        0x24 => '¤',
+        0x5e => '―',
+        0x60 => '‖',
+        0x7e => '¯',
+        0x7f => ' ',
+        0x00..=0x7f => byte as char,
+        _ => UPPER[(byte - 0x80) as usize],
+    }
+}
+
+/// RDS byte for `c`, `None` if the table has no such character
+pub fn from_char(c: char) -> Option<u8> {
+    match c {
+        '¤' => Some(0x24),
+        '―' => Some(0x5e),
+        '‖' => Some(0x60),
+        '¯' => Some(0x7e),
+        // these ASCII characters are replaced by the ones above
+        '$' => Some(0xab),
+        '^' | '`' | '~' | '\x7f' => None,
+        '\0'..='\x7f' => Some(c as u8),
+        _ => UPPER[..0x7f]
+            .iter()
+            .position(|upper| *upper == c)
+            .map(|idx| idx as u8 + 0x80),
+    }
+}
+
+/// Decode RDS bytes into a `heapless::String`, stopping when it is full
+pub fn decode<const N: usize>(bytes: &[u8]) -> String<N> {
+    let mut text = String::new();
+    for byte in bytes {
+        if text.push(to_char(*byte)).is_err() {
+            break;
+        }
+    }
+    text
+}
+
+/// Decode RDS bytes as UTF-8 into `buf`, stopping when it is full
+pub fn decode_into<'a>(bytes: &[u8], buf: &'a mut [u8]) -> &'a str {
+    let mut len = 0;
+    for byte in bytes {
+        let c = to_char(*byte);
+        if len + c.len_utf8() > buf.len() {
+            break;
+        }
+        c.encode_utf8(&mut buf[len..]);
+        len += c.len_utf8();
+    }
+    // only whole characters were written
+    core::str::from_utf8(&buf[..len]).unwrap_or_default()
+}
+
+/// Encode `text` into RDS bytes, characters missing from the table become `replacement`.
+/// Returns the number of bytes written.
+pub fn encode(text: &str, buf: &mut [u8], replacement: u8) -> usize {
+    let mut len = 0;
+    for (c, byte) in text.chars().zip(buf.iter_mut()) {
+        *byte = from_char(c).unwrap_or(replacement);
+        len += 1;
+    }
+    len
+}
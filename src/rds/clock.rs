@@ -0,0 +1,119 @@
+// Clock Time and date, group 4A. UTC as Modified Julian Day, hour and
+// minute, plus the local time offset in half hours.
+
+// Block B: the two highest MJD bits
+const MJD_HIGH_MASK: u16 = 0b11;
+// Block D fields
+const HOUR_LOW_SHIFT: u8 = 12;
+const MINUTE_SHIFT: u8 = 6;
+const MINUTE_MASK: u16 = 0x3f;
+const OFFSET_NEGATIVE: u16 = 1 << 5;
+const OFFSET_MASK: u16 = 0x1f;
+
+// MJD of 1970-01-01
+const MJD_UNIX_EPOCH: u32 = 40_587;
+// MJD is a 17 bit field
+const MJD_MAX: u32 = 0x1_ffff;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub struct ClockTime {
+    /// UTC date and time
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+    pub hour: u8,
+    pub minute: u8,
+    /// Local time offset from UTC in half hours
+    pub offset: i8,
+}
+
+impl ClockTime {
+    /// Decode blocks B, C and D of group 4A, `None` for invalid times
+    /// (e.g. the all zero group of stations not sending CT)
+    pub fn from_blocks(b: u16, c: u16, d: u16) -> Option<Self> {
+        let mjd = ((b & MJD_HIGH_MASK) as u32) << 15 | (c >> 1) as u32;
+        let hour = ((c & 1) << 4 | d >> HOUR_LOW_SHIFT) as u8;
+        let minute = ((d >> MINUTE_SHIFT) & MINUTE_MASK) as u8;
+        let offset = (d & OFFSET_MASK) as i8;
+        if mjd < MJD_UNIX_EPOCH || hour > 23 || minute > 59 {
+            return None;
+        }
+        let (year, month, day) = civil_from_days((mjd - MJD_UNIX_EPOCH) as u64);
+        Some(ClockTime {
+            year: year as u16,
+            month: month as u8,
+            day: day as u8,
+            hour,
+            minute,
+            offset: if d & OFFSET_NEGATIVE != 0 {
+                -offset
+            } else {
+                offset
+            },
+        })
+    }
+
+    /// Blocks B (the low bits only), C and D of group 4A, `None` when a field
+    /// is out of range (see `mjd`, hour, minute or an offset beyond ±31)
+    pub fn blocks(self) -> Option<(u16, u16, u16)> {
+        if self.hour > 23 || self.minute > 59 || self.offset.unsigned_abs() as u16 > OFFSET_MASK {
+            return None;
+        }
+        let mjd = self.mjd()?;
+        let b = (mjd >> 15) as u16 & MJD_HIGH_MASK;
+        let c = (mjd << 1) as u16 | (self.hour as u16 >> 4);
+        let mut d = (self.hour as u16 & 0xf) << HOUR_LOW_SHIFT
+            | (self.minute as u16 & MINUTE_MASK) << MINUTE_SHIFT
+            | (self.offset.unsigned_abs() as u16 & OFFSET_MASK);
+        if self.offset < 0 {
+            d |= OFFSET_NEGATIVE;
+        }
+        Some((b, c, d))
+    }
+
+    /// Modified Julian Day of the UTC date. `None` for invalid dates and
+    /// dates outside the 17 bit MJD range from 1970 on (1970-01-01 to
+    /// 2217-09-27).
+    pub fn mjd(self) -> Option<u32> {
+        if self.year < 1970 || !(1..=12).contains(&self.month) || self.day == 0 {
+            return None;
+        }
+        let days = days_from_civil(self.year as u64, self.month as u64, self.day as u64);
+        // a day past the end of the month shows up as a different date
+        let date = civil_from_days(days);
+        if date != (self.year as u64, self.month as u64, self.day as u64) {
+            return None;
+        }
+        let mjd = days + MJD_UNIX_EPOCH as u64;
+        (mjd <= MJD_MAX as u64).then_some(mjd as u32)
+    }
+}
+
+// days since 1970-01-01 to (year, month, day) and back, valid from 1970 on
+// (`days_from_civil` needs year >= 1970 and month 1 - 12)
+// (http://howardhinnant.github.io/date_algorithms.html)
+pub(crate) fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+    (year, month, day)
+}
+
+pub(crate) fn days_from_civil(year: u64, month: u64, day: u64) -> u64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year / 400;
+    let year_of_era = year % 400;
+    let mp = if month > 2 { month - 3 } else { month + 9 };
+    let day_of_year = (153 * mp + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
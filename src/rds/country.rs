@@ -0,0 +1,144 @@
+// ISO 3166 country codes from the PI country nibble and the Extended Country
+// Code (IEC 62106 annex D)
+
+// ECC and the countries for PI country nibbles 1..=F, "" where unassigned
+const COUNTRIES: [(u8, [&str; 15]); 21] = [
+    (
+        0xa0,
+        [
+            "US", "US", "US", "US", "US", "US", "US", "US", "US", "US", "US", "", "US", "US", "",
+        ],
+    ),
+    (
+        0xa1,
+        [
+            "", "", "", "", "", "", "", "", "", "", "CA", "CA", "CA", "CA", "GL",
+        ],
+    ),
+    (
+        0xa2,
+        [
+            "AI", "AG", "EC", "FK", "BB", "BZ", "KY", "CR", "CU", "AR", "BR", "BM", "AN", "GP",
+            "BS",
+        ],
+    ),
+    (
+        0xa3,
+        [
+            "BO", "CO", "JM", "MQ", "GF", "PY", "NI", "", "PA", "DM", "DO", "CL", "GD", "TC", "GY",
+        ],
+    ),
+    (
+        0xa4,
+        [
+            "GT", "HN", "AW", "", "MS", "TT", "PE", "SR", "UY", "KN", "LC", "SV", "HT", "VE", "",
+        ],
+    ),
+    (
+        0xa5,
+        [
+            "", "", "", "", "", "", "", "", "", "", "MX", "VC", "MX", "MX", "MX",
+        ],
+    ),
+    (
+        0xa6,
+        ["", "", "", "", "", "", "", "", "", "", "", "", "", "", "PM"],
+    ),
+    (
+        0xd0,
+        [
+            "CM", "CF", "DJ", "MG", "ML", "AO", "GQ", "GA", "GN", "ZA", "BF", "CG", "TG", "BJ",
+            "MW",
+        ],
+    ),
+    (
+        0xd1,
+        [
+            "NA", "LR", "GH", "MR", "ST", "CV", "SN", "GM", "BI", "", "BW", "KM", "TZ", "ET", "NG",
+        ],
+    ),
+    (
+        0xd2,
+        [
+            "SL", "ZW", "MZ", "UG", "SZ", "KE", "SO", "NE", "TD", "GW", "CD", "CI", "TZ", "ZM", "",
+        ],
+    ),
+    (
+        0xd3,
+        [
+            "", "", "EH", "", "RW", "LS", "", "SC", "", "MU", "", "SD", "", "", "",
+        ],
+    ),
+    (
+        0xe0,
+        [
+            "DE", "DZ", "AD", "IL", "IT", "BE", "RU", "PS", "AL", "AT", "HU", "MT", "DE", "", "EG",
+        ],
+    ),
+    (
+        0xe1,
+        [
+            "GR", "CY", "SM", "CH", "JO", "FI", "LU", "BG", "DK", "GI", "IQ", "GB", "LY", "RO",
+            "FR",
+        ],
+    ),
+    (
+        0xe2,
+        [
+            "MA", "CZ", "PL", "VA", "SK", "SY", "TN", "", "LI", "IS", "MC", "LT", "RS", "ES", "NO",
+        ],
+    ),
+    (
+        0xe3,
+        [
+            "ME", "IE", "TR", "MK", "TJ", "", "", "NL", "LV", "LB", "AZ", "HR", "KZ", "SE", "BY",
+        ],
+    ),
+    (
+        0xe4,
+        [
+            "MD", "EE", "KG", "", "", "UA", "XK", "PT", "SI", "AM", "UZ", "GE", "", "TM", "BA",
+        ],
+    ),
+    (
+        0xf0,
+        [
+            "AU", "AU", "AU", "AU", "AU", "AU", "AU", "AU", "SA", "AF", "MM", "CN", "KP", "BH",
+            "MY",
+        ],
+    ),
+    (
+        0xf1,
+        [
+            "KI", "BT", "BD", "PK", "FJ", "OM", "NR", "IR", "NZ", "SB", "BN", "LK", "TW", "KR",
+            "HK",
+        ],
+    ),
+    (
+        0xf2,
+        [
+            "KW", "QA", "KH", "WS", "IN", "MO", "", "VN", "PH", "JP", "SG", "MV", "ID", "AE", "NP",
+        ],
+    ),
+    (
+        0xf3,
+        [
+            "VU", "LA", "TH", "TO", "", "", "", "", "", "PG", "", "YE", "", "", "FM",
+        ],
+    ),
+    (
+        0xf4,
+        ["MN", "", "", "", "", "", "", "", "", "", "", "", "", "", ""],
+    ),
+];
+
+/// ISO 3166-1 alpha-2 code of the country using `pi`, `None` for unknown
+/// combinations
+pub fn country_code(pi: u16, ecc: u8) -> Option<&'static str> {
+    let nibble = (pi >> 12) as usize;
+    if nibble == 0 {
+        return None;
+    }
+    let (_, countries) = COUNTRIES.iter().find(|(code, _)| *code == ecc)?;
+    Some(countries[nibble - 1]).filter(|country| !country.is_empty())
+}
@@ -0,0 +1,420 @@
+// Generates RDS groups from a station description, for testing the decoder
+// and the simulator. Groups are sent in a fixed rotation: the four PS
+// segments (carrying the AF list), RadioText, then ECC, PTYN, the RT+ ODA
+// announcement and tags and CT when set.
+
+use heapless::Vec;
+
+use crate::rds::af::{af_code, COUNT_BASE, FILLER, MAX_AF};
+use crate::rds::charset::END_OF_TEXT;
+use crate::rds::clock::ClockTime;
+use crate::rds::radiotext::RT_LEN;
+use crate::rds::rtplus::{RtPlusContentType, RtPlusTag, RT_PLUS_AID};
+use crate::rds::sync::{correct_block, encode_block, BlockQuality, CorrectedGroup, Offset};
+use crate::rds::{Group, GroupType, GroupVersion, MS, PTY_MASK, PTY_SHIFT, TA, TP};
+#[cfg(feature = "ufmt")]
+use crate::text::Text;
+
+// group carrying the RT+ tags
+const RT_PLUS_GROUP: GroupType = GroupType {
+    code: 11,
+    version: GroupVersion::A,
+};
+const MAX_RT_PLUS_TAGS: usize = 2;
+// Group 2 block B fields
+const AB_FLAG: u16 = 1 << 4;
+// RT+ block B fields
+const ITEM_TOGGLE: u16 = 1 << 4;
+const ITEM_RUNNING: u16 = 1 << 3;
+// same as `BlockSync::default`
+const MAX_BURST: u32 = 2;
+const MAX_SLOTS: usize = 32;
+
+/// What the encoder broadcasts. Text fields use the RDS character table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct RdsStation {
+    pub pi: u16,
+    pub pty: u8,
+    pub tp: bool,
+    pub ta: bool,
+    pub music: bool,
+    pub ps: [u8; 8],
+    /// RadioText, not sent when empty
+    pub radiotext: Vec<u8, RT_LEN>,
+    pub rt_ab: bool,
+    /// `B` sends at most 32 characters
+    pub rt_version: GroupVersion,
+    /// Alternative frequencies in kHz
+    pub afs: Vec<u32, MAX_AF>,
+    pub ptyn: Option<[u8; 8]>,
+    /// Extended Country Code
+    pub ecc: Option<u8>,
+    pub clock: Option<ClockTime>,
+    /// RT+ tags, not sent when empty
+    pub rt_plus: Vec<RtPlusTag, MAX_RT_PLUS_TAGS>,
+    pub item_toggle: bool,
+    pub item_running: bool,
+}
+
+impl RdsStation {
+    pub fn new(pi: u16, ps: &[u8; 8]) -> Self {
+        RdsStation {
+            pi,
+            pty: 0,
+            tp: false,
+            ta: false,
+            music: false,
+            ps: *ps,
+            radiotext: Vec::new(),
+            rt_ab: false,
+            rt_version: GroupVersion::A,
+            afs: Vec::new(),
+            ptyn: None,
+            ecc: None,
+            clock: None,
+            rt_plus: Vec::new(),
+            item_toggle: false,
+            item_running: false,
+        }
+    }
+}
+
+#[cfg(feature = "ufmt")]
+impl ufmt::uDebug for RdsStation {
+    fn fmt<W>(&self, f: &mut ufmt::Formatter<'_, W>) -> Result<(), W::Error>
+    where
+        W: ufmt::uWrite + ?Sized,
+    {
+        f.debug_struct("RdsStation")?
+            .field("pi", &self.pi)?
+            .field("pty", &self.pty)?
+            .field("tp", &self.tp)?
+            .field("ta", &self.ta)?
+            .field("ps", &Text(&self.ps))?
+            .field("radiotext", &Text(&self.radiotext))?
+            .field("afs", &self.afs.as_slice())?
+            .field("clock", &self.clock)?
+            .finish()
+    }
+}
+
+/// Bit errors added to the 26 bit blocks of every `interval`-th group
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub struct InjectedErrors {
+    pub interval: u32,
+    /// XORed into blocks A, B, C and D
+    pub bits: [u32; 4],
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+enum Slot {
+    Ps(u8),
+    RadioText(u8),
+    Ecc,
+    Ptyn(u8),
+    RtPlusOda,
+    RtPlus,
+    Clock,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub struct RdsEncoder {
+    station: RdsStation,
+    // position in the rotation
+    slot: usize,
+    // next AF pair sent with PS
+    af_pair: usize,
+    errors: Option<InjectedErrors>,
+    groups: u32,
+}
+
+impl RdsEncoder {
+    pub fn new(station: RdsStation) -> Self {
+        RdsEncoder {
+            station,
+            slot: 0,
+            af_pair: 0,
+            errors: None,
+            groups: 0,
+        }
+    }
+
+    pub fn station(&self) -> &RdsStation {
+        &self.station
+    }
+
+    /// Change what is broadcast, e.g. toggle TA or set a new RadioText
+    pub fn station_mut(&mut self) -> &mut RdsStation {
+        &mut self.station
+    }
+
+    pub fn set_errors(&mut self, errors: Option<InjectedErrors>) {
+        self.errors = errors;
+    }
+
+    /// Number of groups in one rotation
+    pub fn rotation_len(&self) -> usize {
+        self.slots().len()
+    }
+
+    /// Next group of the rotation, without errors
+    pub fn next_group(&mut self) -> Group {
+        let slots = self.slots();
+        let slot = slots[self.slot % slots.len()];
+        self.slot = (self.slot + 1) % slots.len();
+        self.groups = self.groups.wrapping_add(1);
+        match slot {
+            Slot::Ps(segment) => {
+                let group = self.ps_group(segment);
+                self.af_pair = (self.af_pair + 1) % self.af_pairs().len();
+                group
+            }
+            Slot::RadioText(segment) => self.radiotext_group(segment),
+            Slot::Ecc => self.ecc_group(),
+            Slot::Ptyn(segment) => self.ptyn_group(segment),
+            Slot::RtPlusOda => self.rt_plus_oda_group(),
+            Slot::RtPlus => self.rt_plus_group(),
+            Slot::Clock => self.clock_group(),
+        }
+    }
+
+    /// Next group as 26 bit blocks with check words, with the injected
+    /// errors, e.g. for `BlockSync`
+    pub fn next_blocks(&mut self) -> [u32; 4] {
+        let group = self.next_group();
+        let mut blocks = [0; 4];
+        for (i, offset) in offsets(&group).into_iter().enumerate() {
+            blocks[i] = encode_block(group[i], offset);
+        }
+        if let Some(errors) = self.errors {
+            if errors.interval != 0 && self.groups.is_multiple_of(errors.interval) {
+                for (block, bits) in blocks.iter_mut().zip(errors.bits) {
+                    *block ^= bits;
+                }
+            }
+        }
+        blocks
+    }
+
+    /// Next group with the injected errors corrected where possible, like
+    /// the chip does
+    pub fn next_corrected_group(&mut self) -> CorrectedGroup {
+        let blocks = self.next_blocks();
+        let mut group = CorrectedGroup {
+            blocks: [0; 4],
+            quality: [BlockQuality::Valid; 4],
+        };
+        // the offset of block C follows from the (corrected) block B
+        let (b, quality) = correct_block(blocks[1], Offset::B, MAX_BURST);
+        let offsets = offsets(&[0, b, 0, 0]);
+        for (i, offset) in offsets.into_iter().enumerate() {
+            let (data, quality) = match i {
+                1 => (b, quality),
+                _ => correct_block(blocks[i], offset, MAX_BURST),
+            };
+            group.blocks[i] = data;
+            group.quality[i] = quality;
+        }
+        group
+    }
+
+    /// Group 0A with PS segment 0 - 3 and the next part of the AF list
+    pub fn ps_group(&self, segment: u8) -> Group {
+        let segment = segment & 0b11;
+        let mut b = self.block_b(GroupType::new(0, GroupVersion::A)) | segment as u16;
+        if self.station.ta {
+            b |= TA;
+        }
+        if self.station.music {
+            b |= MS;
+        }
+        let start = segment as usize * 2;
+        let d = u16::from_be_bytes([self.station.ps[start], self.station.ps[start + 1]]);
+        let pairs = self.af_pairs();
+        [self.station.pi, b, pairs[self.af_pair % pairs.len()], d]
+    }
+
+    /// Group 2A or 2B with RadioText segment 0 - 15
+    pub fn radiotext_group(&self, segment: u8) -> Group {
+        let segment = segment & 0b1111;
+        let version = self.station.rt_version;
+        let mut b = self.block_b(GroupType::new(2, version)) | segment as u16;
+        if self.station.rt_ab {
+            b |= AB_FLAG;
+        }
+        let width = segment_width(version);
+        let start = segment as usize * width;
+        let char = |pos: usize| self.radiotext_char(start + pos);
+        match version {
+            GroupVersion::A => [
+                self.station.pi,
+                b,
+                u16::from_be_bytes([char(0), char(1)]),
+                u16::from_be_bytes([char(2), char(3)]),
+            ],
+            GroupVersion::B => [
+                self.station.pi,
+                b,
+                self.station.pi,
+                u16::from_be_bytes([char(0), char(1)]),
+            ],
+        }
+    }
+
+    /// Group 1A, variant 0 with the ECC
+    pub fn ecc_group(&self) -> Group {
+        let b = self.block_b(GroupType::new(1, GroupVersion::A));
+        let c = self.station.ecc.unwrap_or_default() as u16;
+        [self.station.pi, b, c, 0]
+    }
+
+    /// Group 10A with PTYN segment 0 or 1
+    pub fn ptyn_group(&self, segment: u8) -> Group {
+        let segment = segment & 1;
+        let b = self.block_b(GroupType::new(10, GroupVersion::A)) | segment as u16;
+        let name = self.station.ptyn.unwrap_or([b' '; 8]);
+        let start = segment as usize * 4;
+        [
+            self.station.pi,
+            b,
+            u16::from_be_bytes([name[start], name[start + 1]]),
+            u16::from_be_bytes([name[start + 2], name[start + 3]]),
+        ]
+    }
+
+    /// Group 3A announcing RT+ in group 11A
+    pub fn rt_plus_oda_group(&self) -> Group {
+        let app_group = (RT_PLUS_GROUP.code as u16) << 1;
+        let b = self.block_b(GroupType::new(3, GroupVersion::A)) | app_group;
+        [self.station.pi, b, 0, RT_PLUS_AID]
+    }
+
+    /// Group 11A with the RT+ tags
+    pub fn rt_plus_group(&self) -> Group {
+        let tag = |i: usize| {
+            self.station
+                .rt_plus
+                .get(i)
+                .map_or((0, 0, 0), |tag: &RtPlusTag| {
+                    (
+                        tag.content_type.code() as u16,
+                        tag.start as u16,
+                        tag.length.saturating_sub(1) as u16,
+                    )
+                })
+        };
+        let (type_1, start_1, length_1) = tag(0);
+        let (type_2, start_2, length_2) = tag(1);
+        let mut b = self.block_b(RT_PLUS_GROUP) | type_1 >> 3;
+        if self.station.item_toggle {
+            b |= ITEM_TOGGLE;
+        }
+        if self.station.item_running {
+            b |= ITEM_RUNNING;
+        }
+        let c =
+            (type_1 & 0b111) << 13 | (start_1 & 0x3f) << 7 | (length_1 & 0x3f) << 1 | type_2 >> 5;
+        let d = (type_2 & 0x1f) << 11 | (start_2 & 0x3f) << 5 | (length_2 & 0x1f);
+        [self.station.pi, b, c, d]
+    }
+
+    /// Group 4A with the clock time, all zero blocks when not set or invalid
+    pub fn clock_group(&self) -> Group {
+        let (b_low, c, d) = self
+            .station
+            .clock
+            .and_then(ClockTime::blocks)
+            .unwrap_or((0, 0, 0));
+        let b = self.block_b(GroupType::new(4, GroupVersion::A)) | b_low;
+        [self.station.pi, b, c, d]
+    }
+
+    fn block_b(&self, group_type: GroupType) -> u16 {
+        let mut b = group_type.block_b_bits() | (self.station.pty as u16) << PTY_SHIFT & PTY_MASK;
+        if self.station.tp {
+            b |= TP;
+        }
+        b
+    }
+
+    // block C of the 0A groups for the whole AF list
+    fn af_pairs(&self) -> Vec<u16, { MAX_AF / 2 + 1 }> {
+        let codes = self.station.afs.iter().filter_map(|freq| af_code(*freq));
+        let count = codes.clone().count() as u8;
+        let mut list: Vec<u8, { MAX_AF + 2 }> = Vec::new();
+        let _ = list.push(COUNT_BASE + count);
+        list.extend(codes);
+        if !list.len().is_multiple_of(2) {
+            let _ = list.push(FILLER);
+        }
+        list.chunks(2)
+            .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+            .collect()
+    }
+
+    fn radiotext_char(&self, pos: usize) -> u8 {
+        let text = &self.station.radiotext;
+        match pos.cmp(&text.len()) {
+            core::cmp::Ordering::Less => text[pos],
+            core::cmp::Ordering::Equal => END_OF_TEXT,
+            core::cmp::Ordering::Greater => b' ',
+        }
+    }
+
+    fn slots(&self) -> Vec<Slot, MAX_SLOTS> {
+        let station = &self.station;
+        let mut slots = Vec::new();
+        for segment in 0..4 {
+            let _ = slots.push(Slot::Ps(segment));
+        }
+        if !station.radiotext.is_empty() {
+            let capacity = segment_width(station.rt_version) * 16;
+            // the end of text marker is sent when the text is shorter
+            let len = (station.radiotext.len() + 1).min(capacity);
+            for segment in 0..len.div_ceil(segment_width(station.rt_version)) {
+                let _ = slots.push(Slot::RadioText(segment as u8));
+            }
+        }
+        if station.ecc.is_some() {
+            let _ = slots.push(Slot::Ecc);
+        }
+        if station.ptyn.is_some() {
+            let _ = slots.push(Slot::Ptyn(0));
+            let _ = slots.push(Slot::Ptyn(1));
+        }
+        if station
+            .rt_plus
+            .iter()
+            .any(|tag| tag.content_type != RtPlusContentType::Dummy)
+        {
+            let _ = slots.push(Slot::RtPlusOda);
+            let _ = slots.push(Slot::RtPlus);
+        }
+        if station.clock.is_some() {
+            let _ = slots.push(Slot::Clock);
+        }
+        slots
+    }
+}
+
+fn segment_width(version: GroupVersion) -> usize {
+    match version {
+        GroupVersion::A => 4,
+        GroupVersion::B => 2,
+    }
+}
+
+// offset words of the blocks, C' for version B groups
+fn offsets(group: &Group) -> [Offset; 4] {
+    let block_c = match GroupType::from_block_b(group[1]).version {
+        GroupVersion::A => Offset::C,
+        GroupVersion::B => Offset::CPrime,
+    };
+    [Offset::A, Offset::B, block_c, Offset::D]
+}
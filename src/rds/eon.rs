@@ -0,0 +1,185 @@
+// Enhanced Other Networks, groups 14A and 14B
+
+use heapless::Vec;
+
+use crate::rds::af::af_frequency;
+#[cfg(feature = "ufmt")]
+use crate::text::Text;
+
+const MAX_NETWORKS: usize = 8;
+const MAX_AF: usize = 8;
+const MAX_MAPPED: usize = 4;
+
+// Block B fields
+const TP_ON: u16 = 1 << 4;
+const TA_ON: u16 = 1 << 3;
+const VARIANT_MASK: u16 = 0b1111;
+// Variant 13 block C fields
+const PTY_ON_SHIFT: u8 = 11;
+const TA_ON_VARIANT_13: u16 = 1;
+
+/// What is known about a network other than the tuned one
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct OtherNetwork {
+    pub pi: u16,
+    ps: [u8; 8],
+    ps_segments: u8,
+    /// Alternative frequencies in kHz (variant 4)
+    pub afs: Vec<u32, MAX_AF>,
+    /// Frequency pairs in kHz (tuned network, other network) from variants 5 - 8
+    pub mapped: Vec<(u32, u32), MAX_MAPPED>,
+    pub pty: u8,
+    pub tp: bool,
+    pub ta: bool,
+    /// Program Item Number
+    pub pin: Option<u16>,
+}
+
+impl OtherNetwork {
+    fn new(pi: u16) -> Self {
+        OtherNetwork {
+            pi,
+            ps: [0; 8],
+            ps_segments: 0,
+            afs: Vec::new(),
+            mapped: Vec::new(),
+            pty: 0,
+            tp: false,
+            ta: false,
+            pin: None,
+        }
+    }
+
+    /// Program Service name, once all four segments were received
+    pub fn ps(&self) -> Option<&[u8; 8]> {
+        if self.ps_segments == 0b1111 {
+            Some(&self.ps)
+        } else {
+            None
+        }
+    }
+
+    /// Frequency to tune to for this network while tuned to `tuned` (kHz).
+    /// Mapped frequencies are preferred over the AF list.
+    pub fn frequency_for(&self, tuned: u32) -> Option<u32> {
+        self.mapped
+            .iter()
+            .find(|(tn, _)| *tn == tuned)
+            .map(|(_, on)| *on)
+            .or_else(|| self.afs.first().copied())
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Eon {
+    networks: Vec<OtherNetwork, MAX_NETWORKS>,
+}
+
+#[cfg(feature = "ufmt")]
+impl ufmt::uDebug for OtherNetwork {
+    fn fmt<W>(&self, f: &mut ufmt::Formatter<'_, W>) -> Result<(), W::Error>
+    where
+        W: ufmt::uWrite + ?Sized,
+    {
+        f.debug_struct("OtherNetwork")?
+            .field("pi", &self.pi)?
+            .field("ps", &Text(&self.ps))?
+            .field("afs", &self.afs.as_slice())?
+            .field("mapped", &self.mapped.as_slice())?
+            .field("pty", &self.pty)?
+            .field("tp", &self.tp)?
+            .field("ta", &self.ta)?
+            .field("pin", &self.pin)?
+            .finish()
+    }
+}
+
+#[cfg(feature = "ufmt")]
+impl ufmt::uDebug for Eon {
+    fn fmt<W>(&self, f: &mut ufmt::Formatter<'_, W>) -> Result<(), W::Error>
+    where
+        W: ufmt::uWrite + ?Sized,
+    {
+        f.debug_struct("Eon")?
+            .field("networks", &self.networks.as_slice())?
+            .finish()
+    }
+}
+
+impl Eon {
+    /// Decode group 14A
+    pub(crate) fn decode_a(&mut self, b: u16, c: u16, d: u16) {
+        let network = self.network(d);
+        network.tp = b & TP_ON != 0;
+        let variant = (b & VARIANT_MASK) as usize;
+        match variant {
+            0..=3 => {
+                network.ps[variant * 2] = (c >> 8) as u8;
+                network.ps[variant * 2 + 1] = c as u8;
+                network.ps_segments |= 1 << variant;
+            }
+            4 => {
+                for code in [(c >> 8) as u8, c as u8] {
+                    if let Some(freq) = af_frequency(code) {
+                        if !network.afs.contains(&freq) {
+                            let _ = network.afs.push(freq);
+                        }
+                    }
+                }
+            }
+            5..=8 => {
+                if let (Some(tn), Some(on)) = (af_frequency((c >> 8) as u8), af_frequency(c as u8))
+                {
+                    match network.mapped.iter_mut().find(|(old, _)| *old == tn) {
+                        Some(pair) => pair.1 = on,
+                        None => {
+                            let _ = network.mapped.push((tn, on));
+                        }
+                    }
+                }
+            }
+            13 => {
+                network.pty = (c >> PTY_ON_SHIFT) as u8 & 0x1f;
+                network.ta = c & TA_ON_VARIANT_13 != 0;
+            }
+            14 => network.pin = Some(c),
+            _ => {}
+        }
+    }
+
+    /// Decode group 14B, returns the new TA state of the other network when it changed
+    pub(crate) fn decode_b(&mut self, b: u16, d: u16) -> Option<bool> {
+        let network = self.network(d);
+        network.tp = b & TP_ON != 0;
+        let ta = b & TA_ON != 0;
+        if network.ta == ta {
+            return None;
+        }
+        network.ta = ta;
+        Some(ta)
+    }
+
+    fn network(&mut self, pi: u16) -> &mut OtherNetwork {
+        let idx = match self.networks.iter().position(|network| network.pi == pi) {
+            Some(idx) => idx,
+            None => {
+                if self.networks.is_full() {
+                    self.networks.remove(0);
+                }
+                let _ = self.networks.push(OtherNetwork::new(pi));
+                self.networks.len() - 1
+            }
+        };
+        &mut self.networks[idx]
+    }
+
+    pub fn networks(&self) -> &[OtherNetwork] {
+        &self.networks
+    }
+
+    pub fn get(&self, pi: u16) -> Option<&OtherNetwork> {
+        self.networks.iter().find(|network| network.pi == pi)
+    }
+}
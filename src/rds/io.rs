@@ -0,0 +1,264 @@
+// Capture and replay of RDS groups in the hex text format of RDS Spy logs,
+// also read by redsea (`-i hex`). One group per line:
+//
+//     D318 0408 E0CD 5241 @2024/05/01 18:30:12.25
+//     D318 ---- E0CD 4449 @2024/05/01 18:30:12.34 # bler 0 3 0 0
+//
+// Blocks that could not be corrected are written as `----`. The optional
+// `@` timestamp (UTC) is the one RDS Spy writes, the optional `# bler`
+// suffix keeps the error levels of the chip (0 - 3, as BLERA/BLERB) and is
+// ignored by the other tools. Capturing from the driver:
+//
+//     let mut writer = HexWriter::new(File::create("capture.txt")?);
+//     if let Some(group) = radio.read_corrected_rds_group()? {
+//         writer.write_group(&group, Some(SystemTime::now()))?;
+//     }
+
+use std::format;
+use std::io::{self, BufRead, Write};
+use std::string::String;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::rds::clock::{civil_from_days, days_from_civil};
+use crate::rds::sync::{BlockQuality, CorrectedGroup};
+use crate::rds::RdsDecoder;
+
+const MISSING_BLOCK: &str = "----";
+const BLER_PREFIX: &str = "# bler";
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+// four digit years as written by `HexWriter`
+const MAX_YEAR: u64 = 9999;
+
+/// One line of a capture
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct HexRecord {
+    pub group: CorrectedGroup,
+    pub timestamp: Option<SystemTime>,
+}
+
+/// Writes groups in the hex text format
+#[derive(Debug)]
+pub struct HexWriter<W> {
+    writer: W,
+}
+
+impl<W: Write> HexWriter<W> {
+    pub fn new(writer: W) -> Self {
+        HexWriter { writer }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    pub fn write_group(
+        &mut self,
+        group: &CorrectedGroup,
+        timestamp: Option<SystemTime>,
+    ) -> io::Result<()> {
+        for (i, (block, quality)) in group.blocks.iter().zip(group.quality).enumerate() {
+            if i > 0 {
+                write!(self.writer, " ")?;
+            }
+            match quality {
+                BlockQuality::Uncorrectable => write!(self.writer, "{}", MISSING_BLOCK)?,
+                _ => write!(self.writer, "{:04X}", block)?,
+            }
+        }
+        if let Some(timestamp) = timestamp {
+            write!(self.writer, " @{}", format_timestamp(timestamp))?;
+        }
+        if group.quality.iter().any(|q| *q != BlockQuality::Valid) {
+            write!(self.writer, " {}", BLER_PREFIX)?;
+            for quality in group.quality {
+                write!(self.writer, " {}", bler(quality))?;
+            }
+        }
+        writeln!(self.writer)
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// Reads the groups of a capture. Blank lines, `#` comments and the
+/// `<...>` header lines of RDS Spy are skipped.
+#[derive(Debug)]
+pub struct HexReader<R> {
+    reader: R,
+    line: String,
+    line_number: usize,
+}
+
+impl<R: BufRead> HexReader<R> {
+    pub fn new(reader: R) -> Self {
+        HexReader {
+            reader,
+            line: String::new(),
+            line_number: 0,
+        }
+    }
+
+    fn invalid(&self, what: &str) -> io::Error {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("line {}: {}", self.line_number, what),
+        )
+    }
+
+    fn parse_line(&self) -> io::Result<Option<HexRecord>> {
+        let line = self.line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with('<') {
+            return Ok(None);
+        }
+        let (line, bler_levels) = match line.split_once(BLER_PREFIX) {
+            Some((line, levels)) => (line, Some(levels)),
+            None => (line, None),
+        };
+        let (blocks_text, timestamp) = match line.split_once('@') {
+            Some((blocks, timestamp)) => (blocks, Some(timestamp.trim())),
+            None => (line, None),
+        };
+
+        let mut group = CorrectedGroup {
+            blocks: [0; 4],
+            quality: [BlockQuality::Valid; 4],
+        };
+        let mut blocks = blocks_text.split_whitespace();
+        for i in 0..4 {
+            let block = blocks.next().ok_or_else(|| self.invalid("missing block"))?;
+            if block == MISSING_BLOCK {
+                group.quality[i] = BlockQuality::Uncorrectable;
+            } else if block.len() == 4 {
+                group.blocks[i] =
+                    u16::from_str_radix(block, 16).map_err(|_| self.invalid("invalid block"))?;
+            } else {
+                return Err(self.invalid("invalid block"));
+            }
+        }
+        if blocks.next().is_some() {
+            return Err(self.invalid("unexpected text after the blocks"));
+        }
+
+        if let Some(levels) = bler_levels {
+            let mut levels = levels.split_whitespace();
+            for quality in group.quality.iter_mut() {
+                let level = levels
+                    .next()
+                    .and_then(|level| level.parse().ok())
+                    .filter(|level| *level <= 3)
+                    .ok_or_else(|| self.invalid("invalid error level"))?;
+                // `----` wins over a level claiming the block was fine
+                if *quality != BlockQuality::Uncorrectable {
+                    *quality = from_bler(level);
+                }
+            }
+        }
+
+        let timestamp = timestamp
+            .map(|timestamp| {
+                parse_timestamp(timestamp).ok_or_else(|| self.invalid("invalid timestamp"))
+            })
+            .transpose()?;
+        Ok(Some(HexRecord { group, timestamp }))
+    }
+}
+
+impl<R: BufRead> Iterator for HexReader<R> {
+    type Item = io::Result<HexRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            self.line.clear();
+            match self.reader.read_line(&mut self.line) {
+                Ok(0) => return None,
+                Ok(_) => {}
+                Err(e) => return Some(Err(e)),
+            }
+            self.line_number += 1;
+            match self.parse_line() {
+                Ok(Some(record)) => return Some(Ok(record)),
+                Ok(None) => {}
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+/// Feed every group of a capture to `decoder`, returns the number of groups
+/// that could be decoded
+pub fn replay<R: BufRead>(reader: R, decoder: &mut RdsDecoder) -> io::Result<usize> {
+    let mut decoded = 0;
+    for record in HexReader::new(reader) {
+        if decoder.decode_corrected(&record?.group).is_some() {
+            decoded += 1;
+        }
+    }
+    Ok(decoded)
+}
+
+// inverse of `CorrectedGroup::from_block_errors`
+fn bler(quality: BlockQuality) -> u8 {
+    match quality {
+        BlockQuality::Valid => 0,
+        BlockQuality::Corrected(bits) if bits <= 2 => 1,
+        BlockQuality::Corrected(_) => 2,
+        BlockQuality::Uncorrectable => 3,
+    }
+}
+
+fn from_bler(level: u8) -> BlockQuality {
+    match level {
+        0 => BlockQuality::Valid,
+        1 => BlockQuality::Corrected(2),
+        2 => BlockQuality::Corrected(5),
+        _ => BlockQuality::Uncorrectable,
+    }
+}
+
+// `YYYY/MM/DD HH:MM:SS.cc` in UTC
+fn format_timestamp(timestamp: SystemTime) -> String {
+    let since_epoch = timestamp.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let seconds = since_epoch.as_secs();
+    let (year, month, day) = civil_from_days(seconds / SECONDS_PER_DAY);
+    let time = seconds % SECONDS_PER_DAY;
+    format!(
+        "{:04}/{:02}/{:02} {:02}:{:02}:{:02}.{:02}",
+        year,
+        month,
+        day,
+        time / 3600,
+        time / 60 % 60,
+        time % 60,
+        since_epoch.subsec_millis() / 10
+    )
+}
+
+fn parse_timestamp(text: &str) -> Option<SystemTime> {
+    let (date, time) = text.split_once(' ')?;
+    let mut date = date.split('/').map(|part| part.parse::<u64>().ok());
+    let (year, month, day) = (date.next()??, date.next()??, date.next()??);
+    let (time, centis) = time.trim().split_once('.').unwrap_or((time.trim(), "0"));
+    let mut time = time.split(':').map(|part| part.parse::<u64>().ok());
+    let (hour, minute, second) = (time.next()??, time.next()??, time.next()??);
+    let centis: u64 = centis.parse().ok()?;
+    if date.next().is_some()
+        || time.next().is_some()
+        || !(1970..=MAX_YEAR).contains(&year)
+        || !(1..=12).contains(&month)
+        || !(1..=31).contains(&day)
+        || hour > 23
+        || minute > 59
+        || second > 60
+        || centis > 99
+    {
+        return None;
+    }
+    let seconds = days_from_civil(year, month, day)
+        .checked_mul(SECONDS_PER_DAY)?
+        .checked_add(hour * 3600 + minute * 60 + second)?;
+    UNIX_EPOCH
+        .checked_add(Duration::from_secs(seconds))?
+        .checked_add(Duration::from_millis(centis * 10))
+}
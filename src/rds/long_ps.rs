@@ -0,0 +1,83 @@
+// Long Program Service name, group 15A. Up to 32 bytes of UTF-8 sent in
+// eight segments of four bytes.
+
+use crate::rds::charset::END_OF_TEXT;
+
+pub const LONG_PS_LEN: usize = 32;
+
+// Block B fields
+const SEGMENT_MASK: u16 = 0b111;
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub struct LongPs {
+    bytes: [u8; LONG_PS_LEN],
+    // bit n set once segment n was received
+    segments: u8,
+    // last complete name, kept while a changed one is being received
+    complete: [u8; LONG_PS_LEN],
+    complete_len: Option<usize>,
+}
+
+impl Default for LongPs {
+    fn default() -> Self {
+        LongPs {
+            bytes: [0; LONG_PS_LEN],
+            segments: 0,
+            complete: [0; LONG_PS_LEN],
+            complete_len: None,
+        }
+    }
+}
+
+impl LongPs {
+    /// Decode group 15A
+    pub(crate) fn decode(&mut self, b: u16, c: u16, d: u16) {
+        let segment = (b & SEGMENT_MASK) as usize;
+        let start = segment * 4;
+        let [c0, c1] = c.to_be_bytes();
+        let [d0, d1] = d.to_be_bytes();
+        let chars = [c0, c1, d0, d1];
+
+        // there is no A/B flag, a segment with new content starts a new name
+        if self.segments & (1 << segment) != 0 && self.bytes[start..start + 4] != chars {
+            self.segments = 0;
+            self.bytes = [0; LONG_PS_LEN];
+        }
+        self.bytes[start..start + 4].copy_from_slice(&chars);
+        self.segments |= 1 << segment;
+
+        if self.is_complete() {
+            self.complete = self.bytes;
+            self.complete_len = Some(self.len());
+        }
+    }
+
+    // position of the end of text marker or the full length
+    fn len(&self) -> usize {
+        self.bytes
+            .iter()
+            .enumerate()
+            .position(|(pos, byte)| *byte == END_OF_TEXT && self.segments & (1 << (pos / 4)) != 0)
+            .unwrap_or(LONG_PS_LEN)
+    }
+
+    /// The last completely received name, `None` before that or when it is
+    /// not valid UTF-8
+    pub fn text(&self) -> Option<&str> {
+        let len = self.complete_len?;
+        core::str::from_utf8(&self.complete[..len]).ok()
+    }
+
+    /// Bytes of the name being received, missing bytes are `0`
+    pub fn raw(&self) -> &[u8; LONG_PS_LEN] {
+        &self.bytes
+    }
+
+    /// `true` once every segment of the name being received arrived
+    pub fn is_complete(&self) -> bool {
+        let needed = self.len().div_ceil(4);
+        (0..needed).all(|segment| self.segments & (1 << segment) != 0)
+    }
+}
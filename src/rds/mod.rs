@@ -0,0 +1,506 @@
+// RDS/RBDS group decoding, fed with the blocks returned by `get_rds_blocks`
+
+use heapless::{Deque, Vec};
+
+use crate::rds::af::AfList;
+use crate::rds::clock::ClockTime;
+use crate::rds::eon::Eon;
+use crate::rds::long_ps::LongPs;
+use crate::rds::ptyn::Ptyn;
+use crate::rds::radiotext::RadioText;
+use crate::rds::rbds::CallSign;
+use crate::rds::rtplus::{RtPlus, RtPlusContentType, RT_PLUS_AID};
+use crate::rds::slc::{ProgramItem, SlowLabelling};
+use crate::rds::sync::{BlockQuality, Confidence, CorrectedGroup};
+use crate::rds::tmc::{Tmc, TmcMessage, TMC_AID, TMC_AID_ALT};
+#[cfg(feature = "ufmt")]
+use crate::text::Text;
+
+pub mod af;
+pub mod charset;
+pub mod clock;
+pub mod country;
+pub mod encoder;
+pub mod eon;
+#[cfg(feature = "std")]
+pub mod io;
+pub mod long_ps;
+pub mod pty;
+pub mod ptyn;
+pub mod radiotext;
+pub mod rbds;
+pub mod rtplus;
+pub mod slc;
+pub mod sync;
+pub mod tmc;
+
+/// One RDS group: blocks A, B, C (or C') and D
+pub type Group = [u16; 4];
+
+// Block B fields
+const GROUP_CODE_SHIFT: u8 = 12;
+pub(crate) const VERSION_B: u16 = 1 << 11;
+const TP: u16 = 1 << 10;
+const PTY_MASK: u16 = 0b0000_0011_1110_0000;
+const PTY_SHIFT: u8 = 5;
+// Group 0 fields
+const TA: u16 = 1 << 4;
+const MS: u16 = 1 << 3;
+const PS_SEGMENT_MASK: u16 = 0b11;
+// Group 3A application group type
+const ODA_GROUP_MASK: u16 = 0b1_1111;
+const MAX_ODA: usize = 8;
+const MAX_EVENTS: usize = 8;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum GroupVersion {
+    A,
+    B,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub struct GroupType {
+    // 0..=15
+    pub code: u8,
+    pub version: GroupVersion,
+}
+
+impl GroupType {
+    pub fn new(code: u8, version: GroupVersion) -> Self {
+        GroupType {
+            code: code & 0x0f,
+            version,
+        }
+    }
+
+    pub fn from_block_b(block_b: u16) -> Self {
+        GroupType {
+            code: (block_b >> GROUP_CODE_SHIFT) as u8,
+            version: if block_b & VERSION_B != 0 {
+                GroupVersion::B
+            } else {
+                GroupVersion::A
+            },
+        }
+    }
+
+    // Type bits already shifted into place for block B
+    pub fn block_b_bits(self) -> u16 {
+        ((self.code as u16) << GROUP_CODE_SHIFT)
+            | match self.version {
+                GroupVersion::A => 0,
+                GroupVersion::B => VERSION_B,
+            }
+    }
+}
+
+/// Open Data Application announced in group 3A
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub struct OdaApplication {
+    pub aid: u16,
+    // Group carrying the application data
+    pub group_type: GroupType,
+    // Application specific message from block C
+    pub message: u16,
+}
+
+/// Something the application may want to react to, see `RdsDecoder::poll_event`
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum Event {
+    /// A linked network (EON) started or stopped a traffic announcement
+    EonTrafficAnnouncement { pi: u16, active: bool },
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RdsDecoder {
+    // interpret PTY (and PI) the North American way
+    rbds: bool,
+    pi: Option<u16>,
+    pty: u8,
+    tp: bool,
+    ta: bool,
+    music: bool,
+    ps: [u8; 8],
+    // bit n set once PS segment n was received
+    ps_segments: u8,
+    af: AfList,
+    clock: Option<ClockTime>,
+    ptyn: Ptyn,
+    long_ps: LongPs,
+    slc: SlowLabelling,
+    radiotext: RadioText,
+    oda: Vec<OdaApplication, MAX_ODA>,
+    rt_plus: RtPlus,
+    eon: Eon,
+    tmc: Tmc,
+    events: Deque<Event, MAX_EVENTS>,
+}
+
+// a summary of the decoded basics, the other services have their own impls
+#[cfg(feature = "defmt")]
+impl defmt::Format for RdsDecoder {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "RdsDecoder {{ pi: {}, pty: {=u8}, tp: {=bool}, ta: {=bool}, ps: {=[u8]:a}, rt: {=[u8]:a} }}",
+            self.pi,
+            self.pty,
+            self.tp,
+            self.ta,
+            self.ps().map_or(&[][..], |ps| ps.as_slice()),
+            self.radiotext.text().unwrap_or_default()
+        );
+    }
+}
+
+#[cfg(feature = "ufmt")]
+impl ufmt::uDebug for RdsDecoder {
+    fn fmt<W>(&self, f: &mut ufmt::Formatter<'_, W>) -> Result<(), W::Error>
+    where
+        W: ufmt::uWrite + ?Sized,
+    {
+        f.debug_struct("RdsDecoder")?
+            .field("pi", &self.pi)?
+            .field("pty", &self.pty)?
+            .field("tp", &self.tp)?
+            .field("ta", &self.ta)?
+            .field("ps", &self.ps().map(|ps| Text(ps.as_slice())))?
+            .field("rt", &self.radiotext.text().map(Text))?
+            .finish()
+    }
+}
+
+impl RdsDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Decoder for RBDS (North America) broadcasts
+    pub fn new_rbds() -> Self {
+        RdsDecoder {
+            rbds: true,
+            ..Self::default()
+        }
+    }
+
+    /// Forget everything, e.g. after tuning to another station
+    pub fn reset(&mut self) {
+        *self = Self {
+            rbds: self.rbds,
+            ..Self::default()
+        };
+    }
+
+    pub fn set_rbds(&mut self, rbds: bool) {
+        self.rbds = rbds;
+    }
+
+    pub fn rbds(&self) -> bool {
+        self.rbds
+    }
+
+    /// Decode one group and return its type
+    pub fn decode(&mut self, group: &Group) -> GroupType {
+        let [a, b, c, d] = *group;
+        let group_type = GroupType::from_block_b(b);
+
+        let pi = match group_type.version {
+            GroupVersion::A => a,
+            // version B groups repeat PI in block C'
+            GroupVersion::B => c,
+        };
+        if self.pi.is_some_and(|old| old != pi) {
+            self.reset();
+        }
+        self.pi = Some(pi);
+        self.tp = b & TP != 0;
+        self.pty = ((b & PTY_MASK) >> PTY_SHIFT) as u8;
+
+        match (group_type.code, group_type.version) {
+            (0, version) => {
+                self.decode_basic_tuning(b, d);
+                if version == GroupVersion::A {
+                    self.af.decode(c);
+                }
+            }
+            (1, GroupVersion::A) => {
+                self.slc.decode(c, d);
+                if let Some(id) = self.slc.tmc_id() {
+                    self.tmc.decode_system_info(id);
+                }
+            }
+            (1, GroupVersion::B) => self.slc.decode_pin(d),
+            (2, version) if self.radiotext.decode(version, b, c, d) => self.rt_plus.clear(),
+            (3, GroupVersion::A) => self.decode_oda(b, c, d),
+            (4, GroupVersion::A) => {
+                if let Some(clock) = ClockTime::from_blocks(b, c, d) {
+                    self.clock = Some(clock);
+                }
+            }
+            (10, GroupVersion::A) => self.ptyn.decode(b, c, d),
+            (14, GroupVersion::A) => self.eon.decode_a(b, c, d),
+            (14, GroupVersion::B) => {
+                if let Some(active) = self.eon.decode_b(b, d) {
+                    self.push_event(Event::EonTrafficAnnouncement { pi: d, active });
+                }
+            }
+            (15, GroupVersion::A) => self.long_ps.decode(b, c, d),
+            _ => {}
+        }
+        if self.oda_group(RT_PLUS_AID) == Some(group_type) {
+            self.rt_plus.decode(b, c, d);
+        }
+        if self.tmc_group() == group_type {
+            self.tmc.decode(b, c, d);
+        }
+        group_type
+    }
+
+    /// Decode a group of uncertain quality. Groups with an unusable block B
+    /// are dropped, a missing PI is taken from block C' or the known PI.
+    /// Returns `None` when the group was dropped.
+    pub fn decode_corrected(&mut self, group: &CorrectedGroup) -> Option<GroupType> {
+        let mut blocks = group.blocks;
+        let bad = |block: usize| group.quality[block] == BlockQuality::Uncorrectable;
+        match group.confidence() {
+            Confidence::Unusable => return None,
+            Confidence::Partial => {
+                let version_b = blocks[1] & VERSION_B != 0;
+                if bad(3) || (bad(2) && !version_b) {
+                    return None;
+                }
+                let pi = match (bad(0), bad(2)) {
+                    (false, _) => blocks[0],
+                    (true, false) if version_b => blocks[2],
+                    _ => self.pi?,
+                };
+                blocks[0] = pi;
+                if version_b {
+                    blocks[2] = pi;
+                }
+            }
+            Confidence::Corrected | Confidence::High => {}
+        }
+        Some(self.decode(&blocks))
+    }
+
+    fn push_event(&mut self, event: Event) {
+        if self.events.is_full() {
+            self.events.pop_front();
+        }
+        let _ = self.events.push_back(event);
+    }
+
+    /// Next pending event, oldest first
+    pub fn poll_event(&mut self) -> Option<Event> {
+        self.events.pop_front()
+    }
+
+    fn decode_oda(&mut self, b: u16, c: u16, d: u16) {
+        let app_group = b & ODA_GROUP_MASK;
+        let application = OdaApplication {
+            aid: d,
+            group_type: GroupType::new(
+                (app_group >> 1) as u8,
+                if app_group & 1 != 0 {
+                    GroupVersion::B
+                } else {
+                    GroupVersion::A
+                },
+            ),
+            message: c,
+        };
+        if matches!(application.aid, TMC_AID | TMC_AID_ALT) {
+            self.tmc.decode_oda_message(c);
+        }
+        match self.oda.iter_mut().find(|oda| oda.aid == application.aid) {
+            Some(oda) => *oda = application,
+            None => {
+                let _ = self.oda.push(application);
+            }
+        }
+    }
+
+    fn decode_basic_tuning(&mut self, b: u16, d: u16) {
+        self.ta = b & TA != 0;
+        self.music = b & MS != 0;
+        let segment = (b & PS_SEGMENT_MASK) as usize;
+        self.ps[segment * 2] = (d >> 8) as u8;
+        self.ps[segment * 2 + 1] = d as u8;
+        self.ps_segments |= 1 << segment;
+    }
+
+    /// Program Identification code
+    pub fn pi(&self) -> Option<u16> {
+        self.pi
+    }
+
+    /// Program Type code (0..=31)
+    pub fn pty(&self) -> u8 {
+        self.pty
+    }
+
+    /// Name of the Program Type, from the RBDS table in RBDS mode
+    pub fn pty_name(&self) -> &'static str {
+        pty::pty_name(self.pty, self.rbds)
+    }
+
+    /// Call letters derived from PI, RBDS mode only
+    pub fn call_sign(&self) -> Option<CallSign> {
+        if !self.rbds {
+            return None;
+        }
+        rbds::pi_to_call_sign(self.pi?)
+    }
+
+    /// Traffic Program
+    pub fn tp(&self) -> bool {
+        self.tp
+    }
+
+    /// Traffic Announcement
+    pub fn ta(&self) -> bool {
+        self.ta
+    }
+
+    /// Music/Speech switch, `true` for music
+    pub fn music(&self) -> bool {
+        self.music
+    }
+
+    /// Program Service name, once all four segments were received.
+    /// Bytes use the RDS character table.
+    pub fn ps(&self) -> Option<&[u8; 8]> {
+        if self.ps_segments == 0b1111 {
+            Some(&self.ps)
+        } else {
+            None
+        }
+    }
+
+    /// Alternative frequencies of the tuned station
+    pub fn af_list(&self) -> &AfList {
+        &self.af
+    }
+
+    /// Last Clock Time received, UTC with the local offset
+    pub fn clock_time(&self) -> Option<ClockTime> {
+        self.clock
+    }
+
+    /// Program Service name as received so far, missing characters are `0`
+    pub fn ps_raw(&self) -> &[u8; 8] {
+        &self.ps
+    }
+
+    /// Program Type Name state, see `ptyn` for the complete name
+    pub fn ptyn_state(&self) -> &Ptyn {
+        &self.ptyn
+    }
+
+    /// Program Type Name, once both segments were received.
+    /// Bytes use the RDS character table.
+    pub fn ptyn(&self) -> Option<&[u8; 8]> {
+        self.ptyn.name()
+    }
+
+    /// Long PS state, see `long_ps` for the complete name
+    pub fn long_ps_state(&self) -> &LongPs {
+        &self.long_ps
+    }
+
+    /// Long Program Service name (UTF-8), the last completely received one
+    pub fn long_ps(&self) -> Option<&str> {
+        self.long_ps.text()
+    }
+
+    /// Group 1 data: ECC, language, TMC identification, PIN, ...
+    pub fn slow_labelling(&self) -> &SlowLabelling {
+        &self.slc
+    }
+
+    /// Extended Country Code
+    pub fn ecc(&self) -> Option<u8> {
+        self.slc.ecc()
+    }
+
+    /// ISO 3166-1 alpha-2 country code from PI and ECC
+    pub fn country(&self) -> Option<&'static str> {
+        country::country_code(self.pi?, self.slc.ecc()?)
+    }
+
+    /// Program Item Number: scheduled start of the current program
+    pub fn pin(&self) -> Option<ProgramItem> {
+        self.slc.pin()
+    }
+
+    /// RadioText state, see `rt` for the complete text
+    pub fn radiotext(&self) -> &RadioText {
+        &self.radiotext
+    }
+
+    /// RadioText, once every character up to the end was received.
+    /// Bytes use the RDS character table.
+    pub fn rt(&self) -> Option<&[u8]> {
+        self.radiotext.text()
+    }
+
+    /// Open Data Applications announced so far
+    pub fn oda_applications(&self) -> &[OdaApplication] {
+        &self.oda
+    }
+
+    /// Group carrying the data of the application `aid`
+    pub fn oda_group(&self, aid: u16) -> Option<GroupType> {
+        self.oda
+            .iter()
+            .find(|oda| oda.aid == aid)
+            .map(|oda| oda.group_type)
+    }
+
+    /// RT+ state of the current RadioText
+    pub fn rt_plus(&self) -> &RtPlus {
+        &self.rt_plus
+    }
+
+    /// Part of the current RadioText tagged as `content_type`, once those
+    /// characters were received
+    pub fn rt_plus_text(&self, content_type: RtPlusContentType) -> Option<&[u8]> {
+        let tag = self.rt_plus.tag(content_type)?;
+        if !self.radiotext.is_received(tag.start as usize, tag.end()) {
+            return None;
+        }
+        Some(&self.radiotext.raw()[tag.start as usize..tag.end()])
+    }
+
+    // TMC uses group 8A unless announced otherwise
+    fn tmc_group(&self) -> GroupType {
+        self.oda_group(TMC_AID)
+            .or_else(|| self.oda_group(TMC_AID_ALT))
+            .unwrap_or(GroupType::new(8, GroupVersion::A))
+    }
+
+    /// RDS-TMC state and service parameters
+    pub fn tmc(&self) -> &Tmc {
+        &self.tmc
+    }
+
+    /// Next new traffic message, each message is returned once even though
+    /// the broadcaster repeats it
+    pub fn poll_tmc_message(&mut self) -> Option<TmcMessage> {
+        self.tmc.poll_message()
+    }
+
+    /// Other networks announced through EON
+    pub fn eon(&self) -> &Eon {
+        &self.eon
+    }
+}
@@ -0,0 +1,77 @@
+// Program Type names, RDS (IEC 62106) and RBDS (NRSC-4-B) interpret PTY differently
+
+const RDS: [&str; 32] = [
+    "None",
+    "News",
+    "Current Affairs",
+    "Information",
+    "Sport",
+    "Education",
+    "Drama",
+    "Culture",
+    "Science",
+    "Varied",
+    "Pop Music",
+    "Rock Music",
+    "Easy Listening",
+    "Light Classical",
+    "Serious Classical",
+    "Other Music",
+    "Weather",
+    "Finance",
+    "Children's Programmes",
+    "Social Affairs",
+    "Religion",
+    "Phone-in",
+    "Travel",
+    "Leisure",
+    "Jazz Music",
+    "Country Music",
+    "National Music",
+    "Oldies Music",
+    "Folk Music",
+    "Documentary",
+    "Alarm Test",
+    "Alarm",
+];
+
+const RBDS: [&str; 32] = [
+    "None",
+    "News",
+    "Information",
+    "Sports",
+    "Talk",
+    "Rock",
+    "Classic Rock",
+    "Adult Hits",
+    "Soft Rock",
+    "Top 40",
+    "Country",
+    "Oldies",
+    "Soft",
+    "Nostalgia",
+    "Jazz",
+    "Classical",
+    "Rhythm and Blues",
+    "Soft Rhythm and Blues",
+    "Foreign Language",
+    "Religious Music",
+    "Religious Talk",
+    "Personality",
+    "Public",
+    "College",
+    "Spanish Talk",
+    "Spanish Music",
+    "Hip Hop",
+    "Unassigned",
+    "Unassigned",
+    "Weather",
+    "Emergency Test",
+    "Emergency",
+];
+
+/// Name of a Program Type code, using the RBDS table when `rbds` is set
+pub fn pty_name(pty: u8, rbds: bool) -> &'static str {
+    let table = if rbds { &RBDS } else { &RDS };
+    table[(pty & 0x1f) as usize]
+}
@@ -0,0 +1,52 @@
+// Program Type Name, group 10A. Eight characters refining the PTY, e.g.
+// "Football" for PTY Sport.
+
+// Block B fields
+const AB_FLAG: u16 = 1 << 4;
+const SEGMENT: u16 = 1;
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub struct Ptyn {
+    name: [u8; 8],
+    // bit n set once segment n was received
+    segments: u8,
+    ab: Option<bool>,
+}
+
+impl Ptyn {
+    /// Decode group 10A, the name is cleared when the A/B flag toggles
+    pub(crate) fn decode(&mut self, b: u16, c: u16, d: u16) {
+        let ab = b & AB_FLAG != 0;
+        if self.ab.is_some_and(|old| old != ab) {
+            *self = Ptyn::default();
+        }
+        self.ab = Some(ab);
+
+        let start = (b & SEGMENT) as usize * 4;
+        self.name[start..start + 2].copy_from_slice(&c.to_be_bytes());
+        self.name[start + 2..start + 4].copy_from_slice(&d.to_be_bytes());
+        self.segments |= 1 << (b & SEGMENT);
+    }
+
+    /// The name, once both segments were received. Bytes use the RDS
+    /// character table.
+    pub fn name(&self) -> Option<&[u8; 8]> {
+        if self.segments == 0b11 {
+            Some(&self.name)
+        } else {
+            None
+        }
+    }
+
+    /// Name as received so far, missing characters are `0`
+    pub fn raw(&self) -> &[u8; 8] {
+        &self.name
+    }
+
+    /// Current state of the A/B flag, `None` before the first group 10A
+    pub fn ab_flag(&self) -> Option<bool> {
+        self.ab
+    }
+}
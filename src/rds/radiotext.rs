@@ -0,0 +1,124 @@
+use crate::rds::charset::END_OF_TEXT;
+use crate::rds::GroupVersion;
+#[cfg(feature = "ufmt")]
+use crate::text::Text;
+
+pub const RT_LEN: usize = 64;
+
+// Group 2 block B fields
+const AB_FLAG: u16 = 1 << 4;
+const SEGMENT_MASK: u16 = 0b1111;
+
+/// RadioText assembled from group 2A (64 characters) or 2B (32 characters)
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct RadioText {
+    text: [u8; RT_LEN],
+    // bit n set once segment n was received
+    segments: u16,
+    ab: Option<bool>,
+    version: GroupVersion,
+    // position of the end of text marker
+    end: Option<usize>,
+}
+
+impl Default for RadioText {
+    fn default() -> Self {
+        RadioText {
+            text: [0; RT_LEN],
+            segments: 0,
+            ab: None,
+            version: GroupVersion::A,
+            end: None,
+        }
+    }
+}
+
+#[cfg(feature = "ufmt")]
+impl ufmt::uDebug for RadioText {
+    fn fmt<W>(&self, f: &mut ufmt::Formatter<'_, W>) -> Result<(), W::Error>
+    where
+        W: ufmt::uWrite + ?Sized,
+    {
+        f.debug_struct("RadioText")?
+            .field("text", &Text(self.raw()))?
+            .field("ab", &self.ab)?
+            .field("version", &self.version)?
+            .field("complete", &self.text().is_some())?
+            .finish()
+    }
+}
+
+impl RadioText {
+    /// Decode a group 2, returns `true` when the previous text was cleared
+    /// because the A/B flag toggled or the group version changed
+    pub(crate) fn decode(&mut self, version: GroupVersion, b: u16, c: u16, d: u16) -> bool {
+        let ab = b & AB_FLAG != 0;
+        let cleared = self
+            .ab
+            .is_some_and(|old| old != ab || self.version != version);
+        if cleared || self.version != version {
+            *self = RadioText::default();
+        }
+        self.ab = Some(ab);
+        self.version = version;
+
+        let segment = (b & SEGMENT_MASK) as usize;
+        let chars = match version {
+            GroupVersion::A => [(c >> 8) as u8, c as u8, (d >> 8) as u8, d as u8],
+            GroupVersion::B => [(d >> 8) as u8, d as u8, 0, 0],
+        };
+        let width = self.segment_width();
+        let start = segment * width;
+        for (pos, char) in chars.iter().take(width).enumerate() {
+            self.text[start + pos] = *char;
+            if *char == END_OF_TEXT && self.end.is_none_or(|end| start + pos < end) {
+                self.end = Some(start + pos);
+            }
+        }
+        self.segments |= 1 << segment;
+        cleared
+    }
+
+    fn segment_width(&self) -> usize {
+        match self.version {
+            GroupVersion::A => 4,
+            GroupVersion::B => 2,
+        }
+    }
+
+    /// Maximum length of the text with the current group version
+    pub fn capacity(&self) -> usize {
+        self.segment_width() * 16
+    }
+
+    /// `true` once every character in `start..end` was received
+    pub fn is_received(&self, start: usize, end: usize) -> bool {
+        let width = self.segment_width();
+        if end > self.capacity() || start > end {
+            return false;
+        }
+        (start / width..end.div_ceil(width)).all(|segment| self.segments & (1 << segment) != 0)
+    }
+
+    /// The complete text up to the end of text marker or the full length.
+    /// Bytes use the RDS character table.
+    pub fn text(&self) -> Option<&[u8]> {
+        let len = self.end.unwrap_or(self.capacity());
+        if self.segments != 0 && self.is_received(0, len) {
+            Some(&self.text[..len])
+        } else {
+            None
+        }
+    }
+
+    /// Text as received so far, missing characters are `0`
+    pub fn raw(&self) -> &[u8] {
+        &self.text[..self.capacity()]
+    }
+
+    /// Current state of the A/B flag, `None` before the first group 2
+    pub fn ab_flag(&self) -> Option<bool> {
+        self.ab
+    }
+}
@@ -0,0 +1,194 @@
+// RBDS (NRSC-4-B) PI code to call letter conversion for North American stations
+
+use heapless::String;
+
+#[cfg(feature = "ufmt")]
+use crate::text::Text;
+
+const K_BASE: u16 = 0x1000;
+const W_BASE: u16 = 0x54a8;
+const THREE_LETTER_BASE: u16 = 0x9950;
+const THREE_LETTER_END: u16 = 0x9eff;
+// 26 * 26 * 26 call letter combinations per K/W block
+const LETTER_COMBINATIONS: u16 = 17_576;
+
+// Three letter call signs, NRSC-4-B table D.7
+const THREE_LETTER: [(u16, &str); 72] = [
+    (0x99a5, "KBW"),
+    (0x99a6, "KCY"),
+    (0x9990, "KDB"),
+    (0x99a7, "KDF"),
+    (0x9950, "KEX"),
+    (0x9951, "KFH"),
+    (0x9952, "KFI"),
+    (0x9953, "KGA"),
+    (0x9991, "KGB"),
+    (0x9954, "KGO"),
+    (0x9955, "KGU"),
+    (0x9956, "KGW"),
+    (0x9957, "KGY"),
+    (0x99aa, "KHQ"),
+    (0x9958, "KID"),
+    (0x9959, "KIT"),
+    (0x995a, "KJR"),
+    (0x995b, "KLO"),
+    (0x995c, "KLZ"),
+    (0x995d, "KMA"),
+    (0x995e, "KMJ"),
+    (0x995f, "KNX"),
+    (0x9960, "KOA"),
+    (0x99ab, "KOB"),
+    (0x9992, "KOY"),
+    (0x9993, "KPQ"),
+    (0x9964, "KQV"),
+    (0x9994, "KSD"),
+    (0x9965, "KSL"),
+    (0x9966, "KUJ"),
+    (0x9995, "KUT"),
+    (0x9967, "KVI"),
+    (0x9968, "KWG"),
+    (0x9996, "KXL"),
+    (0x9997, "KXO"),
+    (0x996b, "KYW"),
+    (0x9999, "WBT"),
+    (0x996d, "WBZ"),
+    (0x996e, "WDZ"),
+    (0x996f, "WEW"),
+    (0x999a, "WGH"),
+    (0x9971, "WGL"),
+    (0x9972, "WGN"),
+    (0x9973, "WGR"),
+    (0x999b, "WGY"),
+    (0x9975, "WHA"),
+    (0x9976, "WHB"),
+    (0x9977, "WHK"),
+    (0x9978, "WHO"),
+    (0x999c, "WHP"),
+    (0x999d, "WIL"),
+    (0x997a, "WIP"),
+    (0x99b3, "WIS"),
+    (0x997b, "WJR"),
+    (0x99b4, "WJW"),
+    (0x99b5, "WJZ"),
+    (0x997c, "WKY"),
+    (0x997d, "WLS"),
+    (0x997e, "WLW"),
+    (0x999e, "WMC"),
+    (0x999f, "WMT"),
+    (0x9981, "WOC"),
+    (0x99a0, "WOI"),
+    (0x9983, "WOL"),
+    (0x9984, "WOR"),
+    (0x99a1, "WOW"),
+    (0x99b9, "WRC"),
+    (0x99a2, "WRR"),
+    (0x99a3, "WSB"),
+    (0x99a4, "WSM"),
+    (0x9988, "WWJ"),
+    (0x9989, "WWL"),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum CallSign {
+    // e.g. "KQED" or "WGN"
+    Letters(String<4>),
+    // Station of a nationally or regionally linked network (PI B0xx, D0xx, E0xx),
+    // the PI carries the network number instead of call letters
+    Linked(u8),
+}
+
+#[cfg(feature = "ufmt")]
+impl ufmt::uDebug for CallSign {
+    fn fmt<W>(&self, f: &mut ufmt::Formatter<'_, W>) -> Result<(), W::Error>
+    where
+        W: ufmt::uWrite + ?Sized,
+    {
+        match self {
+            CallSign::Letters(letters) => f
+                .debug_tuple("Letters")?
+                .field(&Text::from(letters.as_str()))?
+                .finish(),
+            CallSign::Linked(network) => f.debug_tuple("Linked")?.field(network)?.finish(),
+        }
+    }
+}
+
+/// Call letters for a PI code, `None` if the code carries no call sign
+pub fn pi_to_call_sign(pi: u16) -> Option<CallSign> {
+    if matches!(pi & 0xff00, 0xb000 | 0xd000 | 0xe000) {
+        return Some(CallSign::Linked(pi as u8));
+    }
+    // AFxx is shorthand for xx00, Axyz for x0yz
+    let pi = if pi & 0xff00 == 0xaf00 {
+        pi << 8
+    } else if pi & 0xf000 == 0xa000 {
+        ((pi & 0x0f00) << 4) | (pi & 0x00ff)
+    } else {
+        pi
+    };
+
+    let (first, offset) = match pi {
+        K_BASE..W_BASE => ('K', pi - K_BASE),
+        W_BASE..THREE_LETTER_BASE => ('W', pi - W_BASE),
+        THREE_LETTER_BASE..=THREE_LETTER_END => {
+            let (_, letters) = THREE_LETTER.iter().find(|(code, _)| *code == pi)?;
+            return Some(CallSign::Letters(String::try_from(*letters).ok()?));
+        }
+        _ => return None,
+    };
+    let mut letters = String::new();
+    for c in [
+        first,
+        letter(offset / 676),
+        letter(offset / 26 % 26),
+        letter(offset % 26),
+    ] {
+        letters.push(c).ok()?;
+    }
+    Some(CallSign::Letters(letters))
+}
+
+/// PI code K/W call letters are broadcast with, `None` for letters outside the RBDS scheme
+pub fn call_sign_to_pi(call_sign: &str) -> Option<u16> {
+    let bytes = call_sign.as_bytes();
+    if bytes.len() == 3 {
+        return THREE_LETTER
+            .iter()
+            .find(|(_, letters)| letters.eq_ignore_ascii_case(call_sign))
+            .map(|(code, _)| *code);
+    }
+    let [first, rest @ ..] = bytes else {
+        return None;
+    };
+    let base = match first.to_ascii_uppercase() {
+        b'K' => K_BASE,
+        b'W' => W_BASE,
+        _ => return None,
+    };
+    if rest.len() != 3 {
+        return None;
+    }
+    let mut offset = 0;
+    for c in rest {
+        let c = c.to_ascii_uppercase();
+        if !c.is_ascii_uppercase() {
+            return None;
+        }
+        offset = offset * 26 + (c - b'A') as u16;
+    }
+    debug_assert!(offset < LETTER_COMBINATIONS);
+    // xy00 is sent as AFxy, x0yz as Axyz
+    let pi = base + offset;
+    Some(if pi & 0x00ff == 0 {
+        0xaf00 | pi >> 8
+    } else if pi & 0x0f00 == 0 {
+        0xa000 | (pi & 0xf000) >> 4 | (pi & 0x00ff)
+    } else {
+        pi
+    })
+}
+
+fn letter(value: u16) -> char {
+    (b'A' + value as u8) as char
+}
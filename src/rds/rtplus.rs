@@ -0,0 +1,277 @@
+// RadioText Plus (RT+), ODA AID 0x4BD7. Tags mark parts of the current
+// RadioText, e.g. the title and artist of the song being played.
+
+use heapless::Vec;
+
+pub const RT_PLUS_AID: u16 = 0x4bd7;
+const MAX_TAGS: usize = 8;
+
+// Application group block B fields
+const ITEM_TOGGLE: u16 = 1 << 4;
+const ITEM_RUNNING: u16 = 1 << 3;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum RtPlusContentType {
+    Dummy,
+    ItemTitle,
+    ItemAlbum,
+    ItemTrackNumber,
+    ItemArtist,
+    ItemComposition,
+    ItemMovement,
+    ItemConductor,
+    ItemComposer,
+    ItemBand,
+    ItemComment,
+    ItemGenre,
+    InfoNews,
+    InfoNewsLocal,
+    InfoStockmarket,
+    InfoSport,
+    InfoLottery,
+    InfoHoroscope,
+    InfoDailyDiversion,
+    InfoHealth,
+    InfoEvent,
+    InfoScene,
+    InfoCinema,
+    InfoStupidityMachine,
+    InfoDateTime,
+    InfoWeather,
+    InfoTraffic,
+    InfoAlarm,
+    InfoAdvertisement,
+    InfoUrl,
+    InfoOther,
+    StationNameShort,
+    StationNameLong,
+    ProgrammeNow,
+    ProgrammeNext,
+    ProgrammePart,
+    ProgrammeHost,
+    ProgrammeEditorialStaff,
+    ProgrammeFrequency,
+    ProgrammeHomepage,
+    ProgrammeSubchannel,
+    PhoneHotline,
+    PhoneStudio,
+    PhoneOther,
+    SmsStudio,
+    SmsOther,
+    EmailHotline,
+    EmailStudio,
+    EmailOther,
+    MmsOther,
+    Chat,
+    ChatCentre,
+    VoteQuestion,
+    VoteCentre,
+    // 54 - 55
+    Rfu(u8),
+    // 56 - 58
+    Private(u8),
+    Place,
+    Appointment,
+    Identifier,
+    Purchase,
+    GetData,
+}
+
+impl RtPlusContentType {
+    const NAMED: [RtPlusContentType; 54] = [
+        Self::Dummy,
+        Self::ItemTitle,
+        Self::ItemAlbum,
+        Self::ItemTrackNumber,
+        Self::ItemArtist,
+        Self::ItemComposition,
+        Self::ItemMovement,
+        Self::ItemConductor,
+        Self::ItemComposer,
+        Self::ItemBand,
+        Self::ItemComment,
+        Self::ItemGenre,
+        Self::InfoNews,
+        Self::InfoNewsLocal,
+        Self::InfoStockmarket,
+        Self::InfoSport,
+        Self::InfoLottery,
+        Self::InfoHoroscope,
+        Self::InfoDailyDiversion,
+        Self::InfoHealth,
+        Self::InfoEvent,
+        Self::InfoScene,
+        Self::InfoCinema,
+        Self::InfoStupidityMachine,
+        Self::InfoDateTime,
+        Self::InfoWeather,
+        Self::InfoTraffic,
+        Self::InfoAlarm,
+        Self::InfoAdvertisement,
+        Self::InfoUrl,
+        Self::InfoOther,
+        Self::StationNameShort,
+        Self::StationNameLong,
+        Self::ProgrammeNow,
+        Self::ProgrammeNext,
+        Self::ProgrammePart,
+        Self::ProgrammeHost,
+        Self::ProgrammeEditorialStaff,
+        Self::ProgrammeFrequency,
+        Self::ProgrammeHomepage,
+        Self::ProgrammeSubchannel,
+        Self::PhoneHotline,
+        Self::PhoneStudio,
+        Self::PhoneOther,
+        Self::SmsStudio,
+        Self::SmsOther,
+        Self::EmailHotline,
+        Self::EmailStudio,
+        Self::EmailOther,
+        Self::MmsOther,
+        Self::Chat,
+        Self::ChatCentre,
+        Self::VoteQuestion,
+        Self::VoteCentre,
+    ];
+
+    pub fn from_u8(code: u8) -> Self {
+        match code & 0x3f {
+            54..=55 => Self::Rfu(code & 0x3f),
+            56..=58 => Self::Private(code & 0x3f),
+            59 => Self::Place,
+            60 => Self::Appointment,
+            61 => Self::Identifier,
+            62 => Self::Purchase,
+            63 => Self::GetData,
+            code => Self::NAMED[code as usize],
+        }
+    }
+
+    pub fn code(self) -> u8 {
+        match self {
+            Self::Rfu(code) | Self::Private(code) => code,
+            Self::Place => 59,
+            Self::Appointment => 60,
+            Self::Identifier => 61,
+            Self::Purchase => 62,
+            Self::GetData => 63,
+            named => Self::NAMED
+                .iter()
+                .position(|ty| *ty == named)
+                .unwrap_or_default() as u8,
+        }
+    }
+}
+
+/// Part of the RadioText, `start` and `length` are in characters
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub struct RtPlusTag {
+    pub content_type: RtPlusContentType,
+    pub start: u8,
+    pub length: u8,
+}
+
+impl RtPlusTag {
+    pub fn end(&self) -> usize {
+        self.start as usize + self.length as usize
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct RtPlus {
+    item_toggle: Option<bool>,
+    item_running: bool,
+    tags: Vec<RtPlusTag, MAX_TAGS>,
+}
+
+#[cfg(feature = "ufmt")]
+impl ufmt::uDebug for RtPlus {
+    fn fmt<W>(&self, f: &mut ufmt::Formatter<'_, W>) -> Result<(), W::Error>
+    where
+        W: ufmt::uWrite + ?Sized,
+    {
+        f.debug_struct("RtPlus")?
+            .field("item_toggle", &self.item_toggle)?
+            .field("item_running", &self.item_running)?
+            .field("tags", &self.tags.as_slice())?
+            .finish()
+    }
+}
+
+impl RtPlus {
+    /// Decode the RT+ application group carrying two tags
+    pub(crate) fn decode(&mut self, b: u16, c: u16, d: u16) {
+        let toggle = b & ITEM_TOGGLE != 0;
+        if self.item_toggle.is_some_and(|old| old != toggle) {
+            self.tags.clear();
+        }
+        self.item_toggle = Some(toggle);
+        self.item_running = b & ITEM_RUNNING != 0;
+
+        let type_1 = (((b & 0b111) << 3) | (c >> 13)) as u8;
+        let start_1 = ((c >> 7) & 0x3f) as u8;
+        let length_1 = ((c >> 1) & 0x3f) as u8;
+        let type_2 = (((c & 0b1) << 5) | (d >> 11)) as u8;
+        let start_2 = ((d >> 5) & 0x3f) as u8;
+        let length_2 = (d & 0x1f) as u8;
+        for (ty, start, length) in [(type_1, start_1, length_1), (type_2, start_2, length_2)] {
+            let content_type = RtPlusContentType::from_u8(ty);
+            if content_type == RtPlusContentType::Dummy {
+                continue;
+            }
+            // the length marker is the number of characters after the first one
+            self.insert(RtPlusTag {
+                content_type,
+                start,
+                length: length + 1,
+            });
+        }
+    }
+
+    fn insert(&mut self, tag: RtPlusTag) {
+        match self
+            .tags
+            .iter_mut()
+            .find(|old| old.content_type == tag.content_type)
+        {
+            Some(old) => *old = tag,
+            None => {
+                if self.tags.is_full() {
+                    self.tags.remove(0);
+                }
+                let _ = self.tags.push(tag);
+            }
+        }
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.tags.clear();
+    }
+
+    /// Tags for the current RadioText
+    pub fn tags(&self) -> &[RtPlusTag] {
+        &self.tags
+    }
+
+    pub fn tag(&self, content_type: RtPlusContentType) -> Option<&RtPlusTag> {
+        self.tags
+            .iter()
+            .find(|tag| tag.content_type == content_type)
+    }
+
+    /// `true` while the tagged item (e.g. a song) is being broadcast
+    pub fn item_running(&self) -> bool {
+        self.item_running
+    }
+
+    /// Item toggle bit, changes whenever a new item starts
+    pub fn item_toggle(&self) -> Option<bool> {
+        self.item_toggle
+    }
+}
@@ -0,0 +1,123 @@
+// Group 1A Slow Labelling Codes (block C) and Program Item Number (block D)
+
+// Block C fields
+const LINKAGE_ACTUATOR: u16 = 1 << 15;
+const VARIANT_SHIFT: u8 = 12;
+const VARIANT_MASK: u16 = 0b111;
+const DATA_MASK: u16 = 0x0fff;
+
+/// Scheduled start of the current program, from block D of group 1
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub struct ProgramItem {
+    // day of the month, 1..=31
+    pub day: u8,
+    pub hour: u8,
+    pub minute: u8,
+}
+
+impl ProgramItem {
+    /// `None` when the day is 0, i.e. no valid PIN is sent
+    pub fn from_bits(pin: u16) -> Option<Self> {
+        let day = (pin >> 11) as u8;
+        if day == 0 {
+            return None;
+        }
+        Some(ProgramItem {
+            day,
+            hour: ((pin >> 6) & 0x1f) as u8,
+            minute: (pin & 0x3f) as u8,
+        })
+    }
+
+    pub fn bits(self) -> u16 {
+        (self.day as u16) << 11 | (self.hour as u16 & 0x1f) << 6 | (self.minute as u16 & 0x3f)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub struct SlowLabelling {
+    linkage_actuator: bool,
+    ecc: Option<u8>,
+    paging_operator: Option<u8>,
+    tmc_id: Option<u16>,
+    paging_id: Option<u16>,
+    language: Option<u8>,
+    broadcaster_data: Option<u16>,
+    ews_id: Option<u16>,
+    pin: Option<ProgramItem>,
+}
+
+impl SlowLabelling {
+    /// Decode group 1A
+    pub(crate) fn decode(&mut self, c: u16, d: u16) {
+        self.linkage_actuator = c & LINKAGE_ACTUATOR != 0;
+        let data = c & DATA_MASK;
+        match (c >> VARIANT_SHIFT) & VARIANT_MASK {
+            0 => {
+                self.paging_operator = Some((data >> 8) as u8);
+                self.ecc = Some(data as u8);
+            }
+            1 => self.tmc_id = Some(data),
+            2 => self.paging_id = Some(data),
+            3 => self.language = Some(data as u8),
+            6 => self.broadcaster_data = Some(data),
+            7 => self.ews_id = Some(data),
+            _ => {}
+        }
+        self.decode_pin(d);
+    }
+
+    /// Decode block D of group 1A or 1B
+    pub(crate) fn decode_pin(&mut self, d: u16) {
+        self.pin = ProgramItem::from_bits(d);
+    }
+
+    /// Extended Country Code (variant 0)
+    pub fn ecc(&self) -> Option<u8> {
+        self.ecc
+    }
+
+    /// Paging operator code, sent along with the ECC
+    pub fn paging_operator(&self) -> Option<u8> {
+        self.paging_operator
+    }
+
+    /// TMC identification (variant 1)
+    pub fn tmc_id(&self) -> Option<u16> {
+        self.tmc_id
+    }
+
+    /// Paging identification (variant 2)
+    pub fn paging_id(&self) -> Option<u16> {
+        self.paging_id
+    }
+
+    /// Language code of the program (variant 3), IEC 62106 annex J
+    pub fn language(&self) -> Option<u8> {
+        self.language
+    }
+
+    /// Data for broadcaster use (variant 6)
+    pub fn broadcaster_data(&self) -> Option<u16> {
+        self.broadcaster_data
+    }
+
+    /// Emergency Warning System identification (variant 7)
+    pub fn ews_id(&self) -> Option<u16> {
+        self.ews_id
+    }
+
+    /// Linkage actuator, set while a program is linked to other services
+    pub fn linkage_actuator(&self) -> bool {
+        self.linkage_actuator
+    }
+
+    /// Program Item Number
+    pub fn pin(&self) -> Option<ProgramItem> {
+        self.pin
+    }
+}
@@ -0,0 +1,319 @@
+// Software RDS block synchronisation and error correction. Each block is 16
+// data bits followed by a 10 bit check word of the (26,16) shortened cyclic
+// code, XORed with the offset word of the block's position in the group.
+//
+// The RDA5807M only passes on the corrected data words, so correction works
+// on raw 26 bit blocks, e.g. from a software demodulator. For groups read
+// from the chip, `CorrectedGroup::from_block_errors` maps BLERA/BLERB to the
+// same quality scale.
+
+use crate::rds::{Group, VERSION_B};
+
+pub const BLOCK_BITS: u32 = 26;
+const CHECK_BITS: u32 = 10;
+const BLOCK_MASK: u32 = (1 << BLOCK_BITS) - 1;
+// g(x) = x^10 + x^8 + x^7 + x^5 + x^4 + x^3 + 1
+const POLYNOMIAL: u32 = 0x5b9;
+// the code corrects single bursts of up to five bits
+const MAX_BURST: u32 = 5;
+// uncorrectable blocks in a row before sync is dropped
+const SYNC_LOSS_BLOCKS: u8 = 8;
+// blocks between two offset words for sync to be acquired
+const SYNC_MAX_DISTANCE: u32 = 6;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum Offset {
+    A,
+    B,
+    C,
+    CPrime,
+    D,
+    /// RBDS block E
+    E,
+}
+
+impl Offset {
+    pub const fn word(self) -> u16 {
+        match self {
+            Offset::A => 0x0fc,
+            Offset::B => 0x198,
+            Offset::C => 0x168,
+            Offset::CPrime => 0x350,
+            Offset::D => 0x1b4,
+            Offset::E => 0x000,
+        }
+    }
+
+    // position in the group, C and C' share block 3
+    fn index(self) -> Option<usize> {
+        match self {
+            Offset::A => Some(0),
+            Offset::B => Some(1),
+            Offset::C | Offset::CPrime => Some(2),
+            Offset::D => Some(3),
+            Offset::E => None,
+        }
+    }
+
+    fn from_syndrome(syndrome: u16) -> Option<Self> {
+        [Offset::A, Offset::B, Offset::C, Offset::CPrime, Offset::D]
+            .into_iter()
+            .find(|offset| offset.word() == syndrome)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum BlockQuality {
+    Valid,
+    /// Corrected, with up to this many bit errors
+    Corrected(u8),
+    Uncorrectable,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum Confidence {
+    /// Block B is unusable and with it the whole group
+    Unusable,
+    /// Block B is usable, some other block is not
+    Partial,
+    /// All blocks usable, some after correction
+    Corrected,
+    /// All blocks received without errors
+    High,
+}
+
+/// Group with the quality of each block
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub struct CorrectedGroup {
+    pub blocks: Group,
+    pub quality: [BlockQuality; 4],
+}
+
+impl CorrectedGroup {
+    /// Group read from the chip, with BLERA/BLERB from `get_rds_block_errors`.
+    /// The chip reports no errors for blocks C and D.
+    pub fn from_block_errors(blocks: Group, blera: u8, blerb: u8) -> Self {
+        let quality = |bler: u8| match bler {
+            0 => BlockQuality::Valid,
+            1 => BlockQuality::Corrected(2),
+            2 => BlockQuality::Corrected(5),
+            _ => BlockQuality::Uncorrectable,
+        };
+        CorrectedGroup {
+            blocks,
+            quality: [
+                quality(blera),
+                quality(blerb),
+                BlockQuality::Valid,
+                BlockQuality::Valid,
+            ],
+        }
+    }
+
+    pub fn confidence(&self) -> Confidence {
+        if self.quality[1] == BlockQuality::Uncorrectable {
+            Confidence::Unusable
+        } else if self.quality.contains(&BlockQuality::Uncorrectable) {
+            Confidence::Partial
+        } else if self.quality.iter().all(|q| *q == BlockQuality::Valid) {
+            Confidence::High
+        } else {
+            Confidence::Corrected
+        }
+    }
+}
+
+// remainder of `bits` divided by g(x)
+fn remainder(mut bits: u32) -> u16 {
+    for bit in (CHECK_BITS..BLOCK_BITS).rev() {
+        if bits & (1 << bit) != 0 {
+            bits ^= POLYNOMIAL << (bit - CHECK_BITS);
+        }
+    }
+    bits as u16
+}
+
+/// 26 bit block for `data` at `offset`
+pub fn encode_block(data: u16, offset: Offset) -> u32 {
+    let data = (data as u32) << CHECK_BITS;
+    data | (remainder(data) ^ offset.word()) as u32
+}
+
+/// Syndrome of a 26 bit block, equal to the offset word when error free
+pub fn syndrome(block: u32) -> u16 {
+    remainder(block & BLOCK_MASK)
+}
+
+/// Check and, for bursts of up to `max_burst` bits, correct a 26 bit block
+pub fn correct_block(block: u32, offset: Offset, max_burst: u32) -> (u16, BlockQuality) {
+    let block = block & BLOCK_MASK;
+    let error = syndrome(block) ^ offset.word();
+    if error == 0 {
+        return ((block >> CHECK_BITS) as u16, BlockQuality::Valid);
+    }
+    let max_burst = max_burst.min(MAX_BURST);
+    // bursts start and end with an error bit
+    for pattern in (1..1u32 << max_burst).filter(|pattern| pattern & 1 != 0) {
+        for shift in 0..=BLOCK_BITS - (u32::BITS - pattern.leading_zeros()) {
+            let burst = pattern << shift;
+            if remainder(burst) == error {
+                let bits = pattern.count_ones() as u8;
+                return (
+                    ((block ^ burst) >> CHECK_BITS) as u16,
+                    BlockQuality::Corrected(bits),
+                );
+            }
+        }
+    }
+    ((block >> CHECK_BITS) as u16, BlockQuality::Uncorrectable)
+}
+
+/// Finds the block boundaries in a raw RDS bit stream and assembles
+/// corrected groups
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub struct BlockSync {
+    max_burst: u32,
+    // last 26 bits received
+    shift: u32,
+    bits: u32,
+    synced: bool,
+    // bit count and offset of the last offset word seen while searching
+    candidate: Option<(u32, usize)>,
+    // position of the next block in the group
+    next: usize,
+    bad_blocks: u8,
+    group: CorrectedGroup,
+}
+
+impl Default for BlockSync {
+    fn default() -> Self {
+        Self::new(2)
+    }
+}
+
+impl BlockSync {
+    /// Correct bursts of up to `max_burst` (at most 5) bits. Longer bursts
+    /// correct more but also turn more noise into wrong data.
+    pub fn new(max_burst: u32) -> Self {
+        BlockSync {
+            max_burst,
+            shift: 0,
+            bits: 0,
+            synced: false,
+            candidate: None,
+            next: 0,
+            bad_blocks: 0,
+            group: CorrectedGroup {
+                blocks: [0; 4],
+                quality: [BlockQuality::Uncorrectable; 4],
+            },
+        }
+    }
+
+    pub fn is_synced(&self) -> bool {
+        self.synced
+    }
+
+    /// Feed one bit, returns a group once block D was received
+    pub fn push_bit(&mut self, bit: bool) -> Option<CorrectedGroup> {
+        self.shift = ((self.shift << 1) | bit as u32) & BLOCK_MASK;
+        self.bits = self.bits.wrapping_add(1);
+        if self.synced {
+            self.receive()
+        } else {
+            self.search();
+            None
+        }
+    }
+
+    // sync is acquired with two offset words a whole number of blocks apart,
+    // in the right order
+    fn search(&mut self) {
+        if self.bits < BLOCK_BITS {
+            return;
+        }
+        let Some(index) = Offset::from_syndrome(syndrome(self.shift)).and_then(Offset::index)
+        else {
+            return;
+        };
+        if let Some((bits, previous)) = self.candidate {
+            let distance = self.bits.wrapping_sub(bits);
+            let blocks = distance / BLOCK_BITS;
+            if distance % BLOCK_BITS == 0
+                && blocks <= SYNC_MAX_DISTANCE
+                && (previous + blocks as usize) % 4 == index
+            {
+                self.synced = true;
+                self.bad_blocks = 0;
+                self.group.quality = [BlockQuality::Uncorrectable; 4];
+                self.store(
+                    index,
+                    (self.shift >> CHECK_BITS) as u16,
+                    BlockQuality::Valid,
+                );
+                self.bits = 0;
+                return;
+            }
+        }
+        self.candidate = Some((self.bits, index));
+    }
+
+    fn store(&mut self, index: usize, data: u16, quality: BlockQuality) {
+        self.group.blocks[index] = data;
+        self.group.quality[index] = quality;
+        self.next = (index + 1) % 4;
+    }
+
+    fn receive(&mut self) -> Option<CorrectedGroup> {
+        if self.bits < BLOCK_BITS {
+            return None;
+        }
+        self.bits = 0;
+        let index = self.next;
+        let (data, quality) = match index {
+            0 => correct_block(self.shift, Offset::A, self.max_burst),
+            1 => correct_block(self.shift, Offset::B, self.max_burst),
+            2 => {
+                let offset = match self.group.quality[1] {
+                    BlockQuality::Uncorrectable => {
+                        // version unknown, go by the syndrome
+                        match Offset::from_syndrome(syndrome(self.shift)) {
+                            Some(Offset::CPrime) => Offset::CPrime,
+                            _ => Offset::C,
+                        }
+                    }
+                    _ if self.group.blocks[1] & VERSION_B != 0 => Offset::CPrime,
+                    _ => Offset::C,
+                };
+                correct_block(self.shift, offset, self.max_burst)
+            }
+            _ => correct_block(self.shift, Offset::D, self.max_burst),
+        };
+        if index == 0 {
+            self.group.quality = [BlockQuality::Uncorrectable; 4];
+        }
+        self.store(index, data, quality);
+
+        if quality == BlockQuality::Uncorrectable {
+            self.bad_blocks += 1;
+            if self.bad_blocks >= SYNC_LOSS_BLOCKS {
+                self.synced = false;
+                self.candidate = None;
+                return None;
+            }
+        } else {
+            self.bad_blocks = 0;
+        }
+        (index == 3).then_some(self.group)
+    }
+}
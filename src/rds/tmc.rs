@@ -0,0 +1,322 @@
+// RDS-TMC (ISO 14819-1) traffic messages, sent in group 8A. Messages are
+// repeated by the broadcaster, each one is emitted once.
+
+use heapless::{Deque, Vec};
+
+/// ODA application ids of TMC, the second one for encrypted services
+pub const TMC_AID: u16 = 0xcd46;
+pub const TMC_AID_ALT: u16 = 0xcd47;
+
+const MAX_FIELDS: usize = 8;
+const MAX_PENDING: usize = 4;
+const HISTORY_LEN: usize = 16;
+// first group plus up to four continuation groups of 28 bits
+const MAX_DATA_GROUPS: usize = 4;
+const GROUP_DATA_BITS: u32 = 28;
+
+// Block B fields
+const TUNING_INFO: u16 = 1 << 4;
+const SINGLE_GROUP: u16 = 1 << 3;
+const LOW_BITS_MASK: u16 = 0b111;
+// Block C fields
+const DIVERSION: u16 = 1 << 15;
+const FIRST_GROUP: u16 = 1 << 15;
+const SECOND_GROUP: u16 = 1 << 14;
+const NEGATIVE_DIRECTION: u16 = 1 << 14;
+const EXTENT_SHIFT: u8 = 11;
+const EVENT_MASK: u16 = 0x07ff;
+const GSI_SHIFT: u8 = 12;
+const GSI_MASK: u16 = 0b11;
+// System information (3A message variant 0, 1A variant 1)
+const LTN_SHIFT: u8 = 6;
+const AFI: u16 = 1 << 5;
+const MODE: u16 = 1 << 4;
+const SCOPE_MASK: u16 = 0b1111;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum TmcDirection {
+    Positive,
+    Negative,
+}
+
+/// Optional message content, label and value of a free format field
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub struct TmcField {
+    pub label: u8,
+    pub value: u16,
+}
+
+impl TmcField {
+    pub const DURATION: u8 = 0;
+    pub const CONTROL_CODE: u8 = 1;
+    pub const LENGTH_OF_ROUTE: u8 = 2;
+    pub const SPEED_LIMIT: u8 = 3;
+    pub const QUANTIFIER_5: u8 = 4;
+    pub const QUANTIFIER_8: u8 = 5;
+    pub const SUPPLEMENTARY_INFO: u8 = 6;
+    pub const START_TIME: u8 = 7;
+    pub const STOP_TIME: u8 = 8;
+    pub const ADDITIONAL_EVENT: u8 = 9;
+    pub const DIVERSION: u8 = 10;
+    pub const DESTINATION: u8 = 11;
+    pub const CROSS_LINKAGE: u8 = 13;
+    pub const SEPARATOR: u8 = 14;
+
+    // number of value bits following each label
+    const WIDTHS: [u32; 16] = [3, 3, 5, 5, 5, 8, 8, 8, 8, 11, 16, 16, 16, 16, 0, 0];
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TmcMessage {
+    /// Event code (ISO 14819-2 event list)
+    pub event: u16,
+    /// Primary location code in the location table
+    pub location: u16,
+    /// Number of locations the event extends over from the primary one
+    pub extent: u8,
+    pub direction: TmcDirection,
+    /// Duration and persistence, from the single group or field label 0
+    pub duration: u8,
+    /// Diversion advised
+    pub diversion: bool,
+    /// Location table number, once the service parameters were received
+    pub location_table: Option<u8>,
+    /// Free format fields of multi-group messages
+    pub fields: Vec<TmcField, MAX_FIELDS>,
+}
+
+#[cfg(feature = "ufmt")]
+impl ufmt::uDebug for TmcMessage {
+    fn fmt<W>(&self, f: &mut ufmt::Formatter<'_, W>) -> Result<(), W::Error>
+    where
+        W: ufmt::uWrite + ?Sized,
+    {
+        f.debug_struct("TmcMessage")?
+            .field("event", &self.event)?
+            .field("location", &self.location)?
+            .field("extent", &self.extent)?
+            .field("direction", &self.direction)?
+            .field("duration", &self.duration)?
+            .field("diversion", &self.diversion)?
+            .field("location_table", &self.location_table)?
+            .field("fields", &self.fields.as_slice())?
+            .finish()
+    }
+}
+
+/// TMC service parameters from group 3A and 1A
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub struct TmcService {
+    /// Location Table Number
+    pub location_table: u8,
+    /// Alternative Frequency Indicator
+    pub afi: bool,
+    /// Enhanced mode with the transmission timing parameters
+    pub enhanced_mode: bool,
+    /// Message geographical scope bits: international, national, regional, urban
+    pub scope: u8,
+    /// Service Identifier
+    pub sid: Option<u8>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+struct MultiGroup {
+    continuity: u8,
+    message: TmcMessage,
+    // 28 bit chunks, oldest in the highest bits
+    data: u128,
+    groups: usize,
+    // group sequence indicator expected next
+    next_gsi: Option<u8>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Tmc {
+    service: Option<TmcService>,
+    multi: Option<MultiGroup>,
+    // last decoded group, broadcasters send each group twice
+    last_group: Option<(u16, u16, u16)>,
+    history: Deque<TmcMessage, HISTORY_LEN>,
+    pending: Deque<TmcMessage, MAX_PENDING>,
+}
+
+// the message buffers are left out, only what is waiting to be polled
+#[cfg(feature = "defmt")]
+impl defmt::Format for Tmc {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "Tmc {{ service: {}, pending: {=usize} }}",
+            self.service,
+            self.pending.len()
+        );
+    }
+}
+
+#[cfg(feature = "ufmt")]
+impl ufmt::uDebug for Tmc {
+    fn fmt<W>(&self, f: &mut ufmt::Formatter<'_, W>) -> Result<(), W::Error>
+    where
+        W: ufmt::uWrite + ?Sized,
+    {
+        f.debug_struct("Tmc")?
+            .field("service", &self.service)?
+            .field("pending", &self.pending.len())?
+            .finish()
+    }
+}
+
+impl Tmc {
+    /// Service parameters, `None` until announced
+    pub fn service(&self) -> Option<&TmcService> {
+        self.service.as_ref()
+    }
+
+    /// Decode the 3A message of the TMC application
+    pub(crate) fn decode_oda_message(&mut self, message: u16) {
+        match message >> 14 {
+            0 => self.decode_system_info(message),
+            1 => {
+                let service = self.service.get_or_insert_with(TmcService::default);
+                service.sid = Some(((message >> LTN_SHIFT) & 0x3f) as u8);
+            }
+            _ => {}
+        }
+    }
+
+    /// Decode the TMC identification of group 1A, or variant 0 of the 3A message
+    pub(crate) fn decode_system_info(&mut self, info: u16) {
+        let service = self.service.get_or_insert_with(TmcService::default);
+        service.location_table = ((info >> LTN_SHIFT) & 0x3f) as u8;
+        service.afi = info & AFI != 0;
+        service.enhanced_mode = info & MODE != 0;
+        service.scope = (info & SCOPE_MASK) as u8;
+    }
+
+    /// Decode a TMC group (blocks B, C and D)
+    pub(crate) fn decode(&mut self, b: u16, c: u16, d: u16) {
+        if self.last_group.replace((b, c, d)) == Some((b, c, d)) || b & TUNING_INFO != 0 {
+            return;
+        }
+        let low_bits = (b & LOW_BITS_MASK) as u8;
+        if b & SINGLE_GROUP != 0 {
+            let mut message = self.message(c, d);
+            message.duration = low_bits;
+            message.diversion = c & DIVERSION != 0;
+            self.emit(message);
+        } else if c & FIRST_GROUP != 0 {
+            self.multi = Some(MultiGroup {
+                continuity: low_bits,
+                message: self.message(c, d),
+                data: 0,
+                groups: 0,
+                next_gsi: None,
+            });
+        } else {
+            self.decode_continuation(low_bits, c, d);
+        }
+    }
+
+    fn message(&self, c: u16, d: u16) -> TmcMessage {
+        TmcMessage {
+            event: c & EVENT_MASK,
+            location: d,
+            extent: ((c >> EXTENT_SHIFT) & 0b111) as u8,
+            direction: if c & NEGATIVE_DIRECTION != 0 {
+                TmcDirection::Negative
+            } else {
+                TmcDirection::Positive
+            },
+            duration: 0,
+            diversion: false,
+            location_table: self.service.map(|service| service.location_table),
+            fields: Vec::new(),
+        }
+    }
+
+    fn decode_continuation(&mut self, continuity: u8, c: u16, d: u16) {
+        let Some(mut multi) = self.multi.take() else {
+            return;
+        };
+        let gsi = ((c >> GSI_SHIFT) & GSI_MASK) as u8;
+        let second = c & SECOND_GROUP != 0;
+        let in_sequence = match multi.next_gsi {
+            None => second,
+            Some(expected) => !second && gsi == expected,
+        };
+        if multi.continuity != continuity || !in_sequence || multi.groups == MAX_DATA_GROUPS {
+            // a group went missing, drop the message
+            return;
+        }
+        let chunk = ((c as u128 & 0x0fff) << 16) | d as u128;
+        multi.data = (multi.data << GROUP_DATA_BITS) | chunk;
+        multi.groups += 1;
+        if gsi == 0 {
+            let MultiGroup {
+                mut message,
+                data,
+                groups,
+                ..
+            } = multi;
+            parse_fields(&mut message, data, groups as u32 * GROUP_DATA_BITS);
+            self.emit(message);
+        } else {
+            multi.next_gsi = Some(gsi - 1);
+            self.multi = Some(multi);
+        }
+    }
+
+    fn emit(&mut self, message: TmcMessage) {
+        if self.history.iter().any(|old| *old == message) {
+            return;
+        }
+        if self.history.is_full() {
+            self.history.pop_front();
+        }
+        let _ = self.history.push_back(message.clone());
+        if self.pending.is_full() {
+            self.pending.pop_front();
+        }
+        let _ = self.pending.push_back(message);
+    }
+
+    /// Next new message, oldest first
+    pub fn poll_message(&mut self) -> Option<TmcMessage> {
+        self.pending.pop_front()
+    }
+}
+
+// Split `bits` bits of free format data into label/value fields
+fn parse_fields(message: &mut TmcMessage, data: u128, bits: u32) {
+    let mut remaining = bits;
+    let take = |width: u32, remaining: &mut u32| {
+        *remaining -= width;
+        ((data >> *remaining) & ((1 << width) - 1)) as u16
+    };
+    while remaining >= 4 {
+        // the rest is padding
+        if data & ((1 << remaining) - 1) == 0 {
+            break;
+        }
+        let label = take(4, &mut remaining) as u8;
+        let width = TmcField::WIDTHS[label as usize];
+        if width > remaining {
+            break;
+        }
+        let value = take(width, &mut remaining);
+        match label {
+            TmcField::DURATION => message.duration = value as u8,
+            TmcField::DIVERSION => message.diversion = true,
+            _ => {}
+        }
+        let _ = message.fields.push(TmcField { label, value });
+    }
+}
@@ -0,0 +1,122 @@
+use heapless::Vec;
+
+use crate::rds::Group;
+use crate::register_address::{BlendBitFlag, GpioBitFlag, Register, RssiBitFlag, StatusRegister};
+use crate::{Error, Rda5708m, BLOCK_ERROR_UNCORRECTABLE};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub struct RdsMode {
+    /// Verbose mode: groups are passed on with errors and RDSS reports sync
+    pub verbose: bool,
+    /// Buffer received groups in the chip's RDS FIFO
+    pub fifo: bool,
+    /// RBDS mode, block E groups are reported
+    pub rbds: bool,
+}
+
+/// One group read by `drain_rds_fifo`
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub struct RdsFifoGroup {
+    pub blocks: Group,
+    /// The registers held RBDS block E instead of blocks A-D
+    pub block_e: bool,
+    /// RDS decoder synchronized, verbose mode only
+    pub synced: bool,
+    pub blera: u8,
+    pub blerb: u8,
+}
+
+impl RdsFifoGroup {
+    /// The blocks for `RdsDecoder::decode`, `None` for block E or when block A
+    /// or B could not be corrected
+    pub fn group(&self) -> Option<Group> {
+        if self.block_e
+            || self.blera == BLOCK_ERROR_UNCORRECTABLE
+            || self.blerb == BLOCK_ERROR_UNCORRECTABLE
+        {
+            return None;
+        }
+        Some(self.blocks)
+    }
+}
+
+impl<I2C, E> Rda5708m<I2C>
+where
+    I2C: embedded_hal::blocking::i2c::Write<Error = E>
+        + embedded_hal::blocking::i2c::Read<Error = E>
+        + embedded_hal::blocking::i2c::WriteRead<Error = E>,
+{
+    /// Switch verbose, FIFO and RBDS mode. The FIFO is cleared when enabled.
+    pub fn set_rds_mode(&mut self, mode: RdsMode) -> Result<(), Error<E>> {
+        let mut gpio = 0;
+        if mode.rbds {
+            gpio |= GpioBitFlag::RBDS;
+        }
+        if mode.fifo {
+            gpio |= GpioBitFlag::RDS_FIFO_EN | GpioBitFlag::RDS_FIFO_CLR;
+        }
+        self.update_register(
+            Register::RDA5807M_REG_GPIO,
+            GpioBitFlag::RBDS | GpioBitFlag::RDS_FIFO_EN | GpioBitFlag::RDS_FIFO_CLR,
+            gpio,
+        )?;
+        self.update_register(
+            Register::RDA5807M_REG_BLEND,
+            BlendBitFlag::RDS_VERBOSE,
+            if mode.verbose {
+                BlendBitFlag::RDS_VERBOSE
+            } else {
+                0
+            },
+        )
+    }
+
+    pub fn get_rds_mode(&mut self) -> Result<RdsMode, Error<E>> {
+        let gpio = self.read_register(Register::RDA5807M_REG_GPIO)?;
+        let blend = self.read_register(Register::RDA5807M_REG_BLEND)?;
+        Ok(RdsMode {
+            verbose: blend & BlendBitFlag::RDS_VERBOSE != 0,
+            fifo: gpio & GpioBitFlag::RDS_FIFO_EN != 0,
+            rbds: gpio & GpioBitFlag::RBDS != 0,
+        })
+    }
+
+    /// Drop all groups buffered in the RDS FIFO
+    pub fn clear_rds_fifo(&mut self) -> Result<(), Error<E>> {
+        // the chip resets RDS_FIFO_CLR once done
+        self.update_register(
+            Register::RDA5807M_REG_GPIO,
+            GpioBitFlag::RDS_FIFO_CLR,
+            GpioBitFlag::RDS_FIFO_CLR,
+        )
+    }
+
+    /// Read groups while the chip reports one ready, at most `N`. With the
+    /// FIFO enabled this returns everything buffered since the last call.
+    pub fn drain_rds_fifo<const N: usize>(&mut self) -> Result<Vec<RdsFifoGroup, N>, Error<E>> {
+        let mut groups = Vec::new();
+        while !groups.is_full() {
+            let status =
+                StatusRegister::from_u16(self.read_register(Register::RDA5807M_REG_STATUS)?);
+            if !status.rdsr {
+                break;
+            }
+            let rssi = self.read_register(Register::RDA5807M_REG_RSSI)?;
+            let blocks = self.get_rds_blocks()?;
+            let _ = groups.push(RdsFifoGroup {
+                blocks,
+                block_e: rssi & RssiBitFlag::ABCD_E != 0,
+                synced: status.rdss,
+                blera: ((rssi & RssiBitFlag::BLOCK_ERROR_A_MASK)
+                    >> RssiBitFlag::BLOCK_ERROR_A_SHIFT) as u8,
+                blerb: ((rssi & RssiBitFlag::BLOCK_ERROR_B_MASK)
+                    >> RssiBitFlag::BLOCK_ERROR_B_SHIFT) as u8,
+            });
+        }
+        Ok(groups)
+    }
+}
@@ -48,6 +48,7 @@ impl ConfigBitFlags {
     pub const SKMODE: u16 = 1 << 7;
 
     // 晶振频率。
+    pub const CLK_MODE_MASK: u16 = 0b0000_0000_0111_0000;
     // 000：32.768KHz
     pub const CLK_32768_KHZ: u16 = 0b0000_0000_0000_0000;
     // 001：12MHz
@@ -119,6 +120,28 @@ impl TuningBitFlag {
     pub const SPACE_25_KHZ: u16 = 0b0000_0000_0000_0011;
 }
 
+pub struct GpioBitFlag;
+
+impl GpioBitFlag {
+    // Seek/Tune Complete 中断启用
+    pub const STCIEN: u16 = 1 << 14;
+    // 1 = RBDS mode, 0 = RDS mode
+    pub const RBDS: u16 = 1 << 13;
+    // RDS FIFO mode
+    pub const RDS_FIFO_EN: u16 = 1 << 12;
+    // 去加重。0: 75us，1: 50us
+    pub const DE: u16 = 1 << 11;
+    // 1 = clear the RDS FIFO
+    pub const RDS_FIFO_CLR: u16 = 1 << 10;
+    pub const SOFTMUTE_EN: u16 = 1 << 9;
+    // 1 = AFC disabled
+    pub const AFCD: u16 = 1 << 8;
+    pub const I2S_ENABLED: u16 = 1 << 6;
+    pub const GPIO3_MASK: u16 = 0b0000_0000_0011_0000;
+    pub const GPIO2_MASK: u16 = 0b0000_0000_0000_1100;
+    pub const GPIO1_MASK: u16 = 0b0000_0000_0000_0011;
+}
+
 pub struct VolumeBitFlag;
 
 impl VolumeBitFlag {
@@ -153,7 +176,9 @@ impl VolumeBitFlag {
     pub const VOLUME_MASK: u16 = 0b0000_0000_0000_1111;
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
 pub struct VolumeRegister {
     pub int_mode: bool,
     pub seek_mode: u8,
@@ -179,6 +204,40 @@ impl VolumeRegister {
             volume: (data & VolumeBitFlag::VOLUME_MASK) as u8,
         }
     }
+
+    // Fields wider than their bits are truncated
+    pub fn to_u16(&self) -> u16 {
+        let mut data = ((self.seek_mode as u16) << VolumeBitFlag::SEEK_MODE_SHIFT
+            & VolumeBitFlag::SEEK_MODE_MASK)
+            | ((self.seek_th as u16) << VolumeBitFlag::SEEK_TH_SHIFT & VolumeBitFlag::SEEK_TH_MASK)
+            | ((self.lna_port_sel as u16) << VolumeBitFlag::LNA_PORT_SEL_SHIFT
+                & VolumeBitFlag::LNA_PORT_SEL_MASK)
+            | ((self.lna_icsel as u16) << VolumeBitFlag::LNA_ICSEL_BIT_SHIFT
+                & VolumeBitFlag::LNA_ICSEL_BIT_MASK)
+            | (self.volume as u16 & VolumeBitFlag::VOLUME_MASK);
+        if self.int_mode {
+            data |= VolumeBitFlag::INT_MODE;
+        }
+        if self.rsvd {
+            data |= VolumeBitFlag::RSVD;
+        }
+        data
+    }
+}
+
+pub struct BlendBitFlag;
+
+impl BlendBitFlag {
+    pub const TH_SOFTBLEND_MASK: u16 = 0b0111_1100_0000_0000;
+    pub const TH_SOFTBLEND_SHIFT: u8 = 10;
+    // 1 = 65-76MHz band, 0 = 50-76MHz
+    pub const MODE_65M_50M: u16 = 1 << 9;
+    // RDS verbose mode, reserved on early datasheets
+    pub const RDS_VERBOSE: u16 = 1 << 8;
+    pub const SEEK_TH_OLD_MASK: u16 = 0b0000_0000_1111_1100;
+    pub const SEEK_TH_OLD_SHIFT: u8 = 2;
+    pub const SOFTBLEND_EN: u16 = 1 << 1;
+    pub const FREQ_MODE: u16 = 1;
 }
 
 pub struct StatusBitFlag;
@@ -222,7 +281,9 @@ impl StatusBitFlag {
     pub const READ_CHAN_MASK: u16 = 0b0000_0011_1111_1111;
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
 pub struct StatusRegister {
     pub rdsr: bool,
     pub stc: bool,
@@ -245,6 +306,25 @@ impl StatusRegister {
             readchan: data & StatusBitFlag::READ_CHAN_MASK,
         }
     }
+
+    // `readchan` is truncated to its 10 bits
+    pub fn to_u16(&self) -> u16 {
+        let flags = [
+            (self.rdsr, StatusBitFlag::RDSR),
+            (self.stc, StatusBitFlag::STC),
+            (self.sf, StatusBitFlag::SF),
+            (self.rdss, StatusBitFlag::RDDS),
+            (self.blk_e, StatusBitFlag::BLK_E),
+            (self.st, StatusBitFlag::ST),
+        ];
+        let mut data = self.readchan & StatusBitFlag::READ_CHAN_MASK;
+        for (set, bit) in flags {
+            if set {
+                data |= bit;
+            }
+        }
+        data
+    }
 }
 
 pub struct RssiBitFlag;
@@ -253,6 +333,12 @@ impl RssiBitFlag {
     // 信号强度Mask
     pub const RSSI_MASK: u16 = 0b1111_1110_0000_0000;
     pub const RSSI_SHIFT: u8 = 9;
+    // 1 = the current channel is a station
+    pub const FM_TRUE: u16 = 1 << 8;
+    // 1 = FM ready
+    pub const FM_READY: u16 = 1 << 7;
+    // 1 = the RDS block registers hold block E (RBDS), 0 = blocks A-D
+    pub const ABCD_E: u16 = 1 << 4;
 
     // Block error masks
     pub const BLOCK_ERROR_A_MASK: u16 = 0b0000_0000_0000_1100;
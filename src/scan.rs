@@ -0,0 +1,236 @@
+use embedded_hal::blocking::delay::DelayMs;
+use heapless::Vec;
+
+use crate::band::{channel_to_frequency, last_channel, Band, Direction, Spacing};
+use crate::rds::RdsDecoder;
+use crate::register_address::StatusRegister;
+use crate::{Error, Rda5708m};
+
+// STC polling interval
+const POLL_INTERVAL_MS: u32 = 5;
+// RDS polling interval while dwelling on a station
+const RDS_POLL_INTERVAL_MS: u32 = 10;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum ScanMethod {
+    /// Successive hardware seeks, stations are the ones the chip stops at
+    #[default]
+    Seek,
+    /// Tune every channel of the band and keep the ones above `rssi_threshold`
+    Step,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub struct ScanOptions {
+    pub method: ScanMethod,
+    /// Minimum RSSI for a channel to count as a station, with `Seek` only
+    /// for the first channel of the band, which a seek never stops on
+    pub rssi_threshold: u8,
+    /// Maximum time to wait for a tune to complete
+    pub tune_timeout_ms: u32,
+    /// Maximum time to wait for one seek to complete
+    pub seek_timeout_ms: u32,
+    /// How long to listen for PI/PS on each station, 0 disables RDS capture
+    pub rds_dwell_ms: u32,
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        ScanOptions {
+            method: ScanMethod::Seek,
+            rssi_threshold: 20,
+            tune_timeout_ms: 100,
+            seek_timeout_ms: 5_000,
+            rds_dwell_ms: 0,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub struct Station {
+    // kHz
+    pub frequency: u32,
+    pub rssi: u8,
+    pub stereo: bool,
+    pub pi: Option<u16>,
+    // Program Service name, RDS character table
+    pub ps: Option<[u8; 8]>,
+}
+
+impl<I2C, E> Rda5708m<I2C>
+where
+    I2C: embedded_hal::blocking::i2c::Write<Error = E>
+        + embedded_hal::blocking::i2c::Read<Error = E>
+        + embedded_hal::blocking::i2c::WriteRead<Error = E>,
+{
+    /// Walk the current band and return the stations found, in ascending frequency.
+    /// The scan stops early once `N` stations were collected.
+    pub fn scan_band<D: DelayMs<u32>, const N: usize>(
+        &mut self,
+        delay: &mut D,
+        options: &ScanOptions,
+    ) -> Result<Vec<Station, N>, Error<E>> {
+        let (band, spacing, config) = self.get_band_and_spacing()?;
+        let band = Band::from_bits(band);
+        let spacing = Spacing::from_bits(spacing);
+        let last = last_channel(band, spacing);
+        let mut stations = Vec::new();
+
+        match options.method {
+            ScanMethod::Step => {
+                for chan in 0..=last {
+                    self.set_channel_by_old(config, chan)?;
+                    let status = match self.wait_stc(delay, options.tune_timeout_ms)? {
+                        Some(status) => status,
+                        None => continue,
+                    };
+                    let rssi = self.get_rssi()?;
+                    if rssi < options.rssi_threshold {
+                        continue;
+                    }
+                    let frequency = channel_to_frequency(band, spacing, chan);
+                    let station = self.measure_station(delay, options, frequency, rssi, &status)?;
+                    if stations.push(station).is_err() {
+                        break;
+                    }
+                }
+            }
+            ScanMethod::Seek => {
+                self.set_channel_by_old(config, 0)?;
+                let mut previous = None;
+                // seeks start past the current channel, so channel 0 is measured like `Step`
+                if let Some(status) = self.wait_stc(delay, options.tune_timeout_ms)? {
+                    let rssi = self.get_rssi()?;
+                    if rssi >= options.rssi_threshold {
+                        previous = Some(0);
+                        let frequency = channel_to_frequency(band, spacing, 0);
+                        let station =
+                            self.measure_station(delay, options, frequency, rssi, &status)?;
+                        if stations.push(station).is_err() {
+                            return Ok(stations);
+                        }
+                    }
+                }
+                loop {
+                    self.seek_up(false)?;
+                    let status = match self.wait_stc(delay, options.seek_timeout_ms)? {
+                        Some(status) if !status.sf => status,
+                        _ => break,
+                    };
+                    let chan = status.readchan;
+                    // the seek came back to or below a channel already found
+                    if previous.is_some_and(|previous| chan <= previous) || chan > last {
+                        break;
+                    }
+                    previous = Some(chan);
+                    let rssi = self.get_rssi()?;
+                    let frequency = channel_to_frequency(band, spacing, chan);
+                    let station = self.measure_station(delay, options, frequency, rssi, &status)?;
+                    if stations.push(station).is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+        Ok(stations)
+    }
+
+    fn measure_station<D: DelayMs<u32>>(
+        &mut self,
+        delay: &mut D,
+        options: &ScanOptions,
+        frequency: u32,
+        rssi: u8,
+        status: &StatusRegister,
+    ) -> Result<Station, Error<E>> {
+        let mut station = Station {
+            frequency,
+            rssi,
+            stereo: status.st,
+            pi: None,
+            ps: None,
+        };
+        if options.rds_dwell_ms == 0 {
+            return Ok(station);
+        }
+
+        let mut decoder = RdsDecoder::new();
+        let mut elapsed = 0;
+        while elapsed < options.rds_dwell_ms {
+            if let Some(group) = self.read_rds_group()? {
+                decoder.decode(&group);
+                if decoder.pi().is_some() && decoder.ps().is_some() {
+                    break;
+                }
+            }
+            delay.delay_ms(RDS_POLL_INTERVAL_MS);
+            elapsed += RDS_POLL_INTERVAL_MS;
+        }
+        station.pi = decoder.pi();
+        station.ps = decoder.ps().copied();
+        Ok(station)
+    }
+
+    /// Wait for a tune or seek started before to complete, failing with
+    /// `Error::Timeout` after `timeout_ms`
+    pub fn wait_tune_complete<D: DelayMs<u32>>(
+        &mut self,
+        delay: &mut D,
+        timeout_ms: u32,
+    ) -> Result<StatusRegister, Error<E>> {
+        self.wait_stc(delay, timeout_ms)?.ok_or(Error::Timeout)
+    }
+
+    /// Seek to the next station and wait for it, returning its frequency in kHz.
+    /// Fails with `Error::SeekFailed` when the seek found nothing (or stopped at
+    /// the band edge without `wrap`) and `Error::Timeout` after `timeout_ms`.
+    pub fn seek<D: DelayMs<u32>>(
+        &mut self,
+        direction: Direction,
+        wrap: bool,
+        delay: &mut D,
+        timeout_ms: u32,
+    ) -> Result<u32, Error<E>> {
+        match direction {
+            Direction::Up => self.seek_up(wrap)?,
+            Direction::Down => self.seek_down(wrap)?,
+        }
+        let status = self.wait_tune_complete(delay, timeout_ms)?;
+        if status.sf {
+            return Err(Error::SeekFailed);
+        }
+        let (band, spacing, _) = self.get_band_and_spacing()?;
+        Ok(channel_to_frequency(
+            Band::from_bits(band),
+            Spacing::from_bits(spacing),
+            status.readchan,
+        ))
+    }
+
+    /// Poll the status register until the seek/tune complete flag is set.
+    /// Returns `None` when `timeout_ms` elapsed first.
+    pub(crate) fn wait_stc<D: DelayMs<u32>>(
+        &mut self,
+        delay: &mut D,
+        timeout_ms: u32,
+    ) -> Result<Option<StatusRegister>, Error<E>> {
+        let mut elapsed = 0;
+        loop {
+            let status = self.get_status()?;
+            if status.stc {
+                return Ok(Some(status));
+            }
+            if elapsed >= timeout_ms {
+                return Ok(None);
+            }
+            delay.delay_ms(POLL_INTERVAL_MS);
+            elapsed += POLL_INTERVAL_MS;
+        }
+    }
+}
@@ -0,0 +1,236 @@
+use crate::register_address::{Register, RssiBitFlag, StatusBitFlag};
+use crate::{Error, Rda5708m, BLOCK_ERROR_UNCORRECTABLE};
+
+// Smoothed values are kept with 8 fractional bits
+const FRACTION_BITS: u8 = 8;
+pub const MAX_QUALITY: u8 = 5;
+
+/// One reading of registers 0x0A and 0x0B
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub struct SignalSample {
+    // dBµV
+    pub rssi: u8,
+    pub stereo: bool,
+    pub fm_true: bool,
+    pub fm_ready: bool,
+    // A new RDS group was ready, block errors are only meaningful then
+    pub rds_ready: bool,
+    pub blera: u8,
+    pub blerb: u8,
+}
+
+impl SignalSample {
+    pub fn from_registers(status: u16, rssi: u16) -> Self {
+        SignalSample {
+            rssi: ((rssi & RssiBitFlag::RSSI_MASK) >> RssiBitFlag::RSSI_SHIFT) as u8,
+            stereo: status & StatusBitFlag::ST != 0,
+            fm_true: rssi & RssiBitFlag::FM_TRUE != 0,
+            fm_ready: rssi & RssiBitFlag::FM_READY != 0,
+            rds_ready: status & StatusBitFlag::RDSR != 0,
+            blera: ((rssi & RssiBitFlag::BLOCK_ERROR_A_MASK) >> RssiBitFlag::BLOCK_ERROR_A_SHIFT)
+                as u8,
+            blerb: ((rssi & RssiBitFlag::BLOCK_ERROR_B_MASK) >> RssiBitFlag::BLOCK_ERROR_B_SHIFT)
+                as u8,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum SignalState {
+    Good,
+    Weak,
+    Lost,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub struct SignalMonitorConfig {
+    /// Weight of a new sample is 1 / 2^smoothing, values above 31 act as 31
+    pub smoothing: u8,
+    /// Smoothed RSSI (dBµV) at or above which the signal is good
+    pub good_threshold: u8,
+    /// Smoothed RSSI (dBµV) below which the signal is lost
+    pub lost_threshold: u8,
+    /// dB the RSSI has to move past a threshold before the state changes
+    pub hysteresis: u8,
+    /// dB per quality bar above `lost_threshold`
+    pub bar_step: u8,
+}
+
+impl Default for SignalMonitorConfig {
+    fn default() -> Self {
+        SignalMonitorConfig {
+            smoothing: 2,
+            good_threshold: 30,
+            lost_threshold: 12,
+            hysteresis: 3,
+            bar_step: 8,
+        }
+    }
+}
+
+/// Smooths successive `SignalSample`s and reports good/weak/lost transitions
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub struct SignalMonitor {
+    config: SignalMonitorConfig,
+    // RSSI and RDS block error rate (0..=100%), fixed point
+    rssi: Option<u32>,
+    rds_error_rate: u32,
+    state: Option<SignalState>,
+    last: SignalSample,
+}
+
+impl SignalMonitor {
+    pub fn new(config: SignalMonitorConfig) -> Self {
+        SignalMonitor {
+            config,
+            rssi: None,
+            rds_error_rate: 0,
+            state: None,
+            last: SignalSample::default(),
+        }
+    }
+
+    /// Forget the history, e.g. after tuning to another station
+    pub fn reset(&mut self) {
+        *self = Self::new(self.config);
+    }
+
+    /// Feed a sample, returns the new state when it changed
+    pub fn update(&mut self, sample: &SignalSample) -> Option<SignalState> {
+        let rssi = (sample.rssi as u32) << FRACTION_BITS;
+        self.rssi = Some(match self.rssi {
+            Some(smoothed) => smooth(smoothed, rssi, self.config.smoothing),
+            None => rssi,
+        });
+        if sample.rds_ready {
+            let errors = [sample.blera, sample.blerb]
+                .iter()
+                .filter(|bler| **bler == BLOCK_ERROR_UNCORRECTABLE)
+                .count() as u32;
+            let rate = (errors * 50) << FRACTION_BITS;
+            self.rds_error_rate = smooth(self.rds_error_rate, rate, self.config.smoothing);
+        }
+        self.last = *sample;
+
+        let state = self.next_state();
+        if self.state == Some(state) {
+            return None;
+        }
+        self.state = Some(state);
+        Some(state)
+    }
+
+    fn next_state(&self) -> SignalState {
+        let rssi = self.rssi_dbuv();
+        let SignalMonitorConfig {
+            good_threshold: good,
+            lost_threshold: lost,
+            hysteresis,
+            ..
+        } = self.config;
+        let above = |threshold: u8| rssi >= threshold.saturating_add(hysteresis);
+        let below = |threshold: u8| rssi < threshold.saturating_sub(hysteresis);
+        match self.state {
+            None if rssi >= good => SignalState::Good,
+            None if rssi >= lost => SignalState::Weak,
+            None => SignalState::Lost,
+            Some(SignalState::Good) if below(lost) => SignalState::Lost,
+            Some(SignalState::Good) if below(good) => SignalState::Weak,
+            Some(SignalState::Weak) if above(good) => SignalState::Good,
+            Some(SignalState::Weak) if below(lost) => SignalState::Lost,
+            Some(SignalState::Lost) if above(good) => SignalState::Good,
+            Some(SignalState::Lost) if above(lost) => SignalState::Weak,
+            Some(state) => state,
+        }
+    }
+
+    /// Current state, `None` before the first sample
+    pub fn state(&self) -> Option<SignalState> {
+        self.state
+    }
+
+    /// Smoothed RSSI in dBµV
+    pub fn rssi_dbuv(&self) -> u8 {
+        self.rssi.map_or(0, round)
+    }
+
+    /// Signal quality from 0 (lost) to `MAX_QUALITY` bars
+    pub fn quality(&self) -> u8 {
+        match self.state {
+            None | Some(SignalState::Lost) => 0,
+            Some(_) => {
+                let above = self.rssi_dbuv().saturating_sub(self.config.lost_threshold);
+                (1 + above / self.config.bar_step.max(1)).min(MAX_QUALITY)
+            }
+        }
+    }
+
+    /// Smoothed share of RDS blocks A/B received with uncorrectable errors, in percent
+    pub fn rds_error_rate(&self) -> u8 {
+        round(self.rds_error_rate)
+    }
+
+    pub fn stereo(&self) -> bool {
+        self.last.stereo
+    }
+
+    pub fn fm_true(&self) -> bool {
+        self.last.fm_true
+    }
+
+    pub fn fm_ready(&self) -> bool {
+        self.last.fm_ready
+    }
+}
+
+impl Default for SignalMonitor {
+    fn default() -> Self {
+        Self::new(SignalMonitorConfig::default())
+    }
+}
+
+// the largest shift of a u32 difference
+const MAX_SMOOTHING: u8 = 31;
+
+fn smooth(smoothed: u32, sample: u32, shift: u8) -> u32 {
+    let shift = shift.min(MAX_SMOOTHING);
+    if sample >= smoothed {
+        smoothed + ((sample - smoothed) >> shift)
+    } else {
+        smoothed - ((smoothed - sample) >> shift)
+    }
+}
+
+fn round(fixed: u32) -> u8 {
+    ((fixed + (1 << (FRACTION_BITS - 1))) >> FRACTION_BITS) as u8
+}
+
+impl<I2C, E> Rda5708m<I2C>
+where
+    I2C: embedded_hal::blocking::i2c::Write<Error = E>
+        + embedded_hal::blocking::i2c::Read<Error = E>
+        + embedded_hal::blocking::i2c::WriteRead<Error = E>,
+{
+    pub fn get_signal_sample(&mut self) -> Result<SignalSample, Error<E>> {
+        let status = self.read_register(Register::RDA5807M_REG_STATUS)?;
+        let rssi = self.read_register(Register::RDA5807M_REG_RSSI)?;
+        Ok(SignalSample::from_registers(status, rssi))
+    }
+
+    /// Take a sample and feed it to `monitor`, returns the new state when it changed
+    pub fn sample_signal(
+        &mut self,
+        monitor: &mut SignalMonitor,
+    ) -> Result<Option<SignalState>, Error<E>> {
+        let sample = self.get_signal_sample()?;
+        Ok(monitor.update(&sample))
+    }
+}
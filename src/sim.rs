@@ -0,0 +1,500 @@
+// Behavioural model of the RDA5807M for testing without hardware. The chip
+// answers on the I²C traits like the real one: registers 0x00-0x0F, tune and
+// seek complete after a while, RSSI/stereo come from a virtual spectrum and
+// RDS groups are delivered one per group interval.
+//
+// Time only passes through `advance` or the delay returned by `delay`, so
+// tests are deterministic. The I²C traits are implemented for `&SimChip` too,
+// letting a test keep a handle to the chip while the driver owns the bus.
+
+use core::cell::RefCell;
+
+use embedded_hal::blocking::delay::DelayMs;
+use embedded_hal::blocking::i2c::{Read, Write, WriteRead};
+use heapless::{Deque, Vec};
+
+use crate::band::{channel_to_frequency, last_channel, Band, Spacing};
+use crate::rds::Group;
+use crate::register_address::{
+    ConfigBitFlags, Register, RssiBitFlag, StatusBitFlag, TuningBitFlag, VolumeBitFlag,
+};
+use crate::{DEVICE_BASE_RANDOM_ADDRESS, DEVICE_BASE_SEQ_ADDRESS, DEVICE_ID};
+
+pub const MAX_STATIONS: usize = 16;
+pub const MAX_STATION_GROUPS: usize = 16;
+const MAX_INJECTED: usize = 16;
+const REGISTER_COUNT: usize = 16;
+// sequential reads start at the status register
+const SEQ_READ_START: usize = Register::RDA5807M_REG_STATUS as usize;
+
+/// Time for a tune to complete
+pub const TUNE_TIME_MS: u32 = 10;
+/// Time a seek spends on each channel it passes
+pub const SEEK_TIME_PER_CHANNEL_MS: u32 = 4;
+/// One RDS group takes 87.6ms on air
+pub const RDS_GROUP_INTERVAL_MS: u32 = 88;
+
+// Power-on register values
+const RESET_REGISTERS: [u16; REGISTER_COUNT] = [
+    DEVICE_ID, 0, 0, 0, 0x0400, 0x888b, 0, 0x4202, 0, 0, 0, 0, 0, 0, 0, 0,
+];
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SimError {
+    /// Nobody answers on this address
+    Nack,
+    /// Transfer without a register address or past register 0x0F
+    InvalidTransfer,
+}
+
+/// A station of the virtual spectrum
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SimStation {
+    // kHz
+    pub frequency: u32,
+    pub rssi: u8,
+    pub stereo: bool,
+    /// Groups broadcast in a loop while tuned to the station
+    pub rds: Vec<Group, MAX_STATION_GROUPS>,
+}
+
+impl SimStation {
+    pub fn new(frequency: u32, rssi: u8, stereo: bool) -> Self {
+        SimStation {
+            frequency,
+            rssi,
+            stereo,
+            rds: Vec::new(),
+        }
+    }
+
+    /// Broadcast `groups`, at most `MAX_STATION_GROUPS`
+    pub fn with_rds(mut self, groups: &[Group]) -> Self {
+        self.rds.clear();
+        for group in groups.iter().take(MAX_STATION_GROUPS) {
+            let _ = self.rds.push(*group);
+        }
+        self
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Operation {
+    Tune,
+    Seek { found: bool },
+}
+
+#[derive(Debug, Clone)]
+struct State {
+    registers: [u16; REGISTER_COUNT],
+    now_ms: u32,
+    chan: u16,
+    // operation in progress and when it completes
+    pending: Option<(u32, Operation)>,
+    stations: Vec<SimStation, MAX_STATIONS>,
+    noise_rssi: u8,
+    injected: Deque<Group, MAX_INJECTED>,
+    next_group_ms: u32,
+    group_index: usize,
+}
+
+impl State {
+    fn new() -> Self {
+        State {
+            registers: RESET_REGISTERS,
+            now_ms: 0,
+            chan: 0,
+            pending: None,
+            stations: Vec::new(),
+            noise_rssi: 5,
+            injected: Deque::new(),
+            next_group_ms: RDS_GROUP_INTERVAL_MS,
+            group_index: 0,
+        }
+    }
+
+    fn band_and_spacing(&self) -> (Band, Spacing) {
+        let tuning = self.registers[Register::RDA5807M_REG_TUNING as usize];
+        (
+            Band::from_bits(
+                ((tuning & TuningBitFlag::BAND_MASK) >> TuningBitFlag::BAND_SHIFT) as u8,
+            ),
+            Spacing::from_bits((tuning & TuningBitFlag::SPACE_MASK) as u8),
+        )
+    }
+
+    fn frequency(&self, chan: u16) -> u32 {
+        let (band, spacing) = self.band_and_spacing();
+        channel_to_frequency(band, spacing, chan)
+    }
+
+    fn station(&self, chan: u16) -> Option<&SimStation> {
+        let frequency = self.frequency(chan);
+        self.stations
+            .iter()
+            .find(|station| station.frequency == frequency)
+    }
+
+    fn rssi(&self, chan: u16) -> u8 {
+        self.station(chan)
+            .map_or(self.noise_rssi, |station| station.rssi)
+    }
+
+    fn enabled(&self) -> bool {
+        self.registers[Register::RDA5807M_REG_CONFIG as usize] & ConfigBitFlags::ENABLE != 0
+    }
+
+    fn write(&mut self, register: usize, value: u16) {
+        match register as u8 {
+            // chip id and status registers are read only
+            Register::RDA5807M_REG_CHIPID | 0x01 | 0x0a..=0x0f => {}
+            Register::RDA5807M_REG_CONFIG => {
+                if value & ConfigBitFlags::RESET != 0 {
+                    self.registers = RESET_REGISTERS;
+                    self.pending = None;
+                    return;
+                }
+                self.registers[register] = value;
+                if value & ConfigBitFlags::SEEK != 0 && self.enabled() {
+                    self.start_seek(value);
+                }
+            }
+            Register::RDA5807M_REG_TUNING => {
+                self.registers[register] = value;
+                if value & TuningBitFlag::TUNE != 0 && self.enabled() {
+                    self.chan = (value & TuningBitFlag::CHAN_MASK) >> TuningBitFlag::CHAN_SHIFT;
+                    self.start(TUNE_TIME_MS, Operation::Tune);
+                }
+            }
+            _ => self.registers[register] = value,
+        }
+    }
+
+    fn start(&mut self, duration_ms: u32, operation: Operation) {
+        self.registers[Register::RDA5807M_REG_STATUS as usize] &=
+            !(StatusBitFlag::STC | StatusBitFlag::SF);
+        self.pending = Some((self.now_ms.wrapping_add(duration_ms), operation));
+        self.restart_rds();
+    }
+
+    fn start_seek(&mut self, config: u16) {
+        let (band, spacing) = self.band_and_spacing();
+        let last = last_channel(band, spacing);
+        let up = config & ConfigBitFlags::SEEKUP != 0;
+        let wrap = config & ConfigBitFlags::SKMODE == 0;
+        let volume = self.registers[Register::RDA5807M_REG_VOLUME as usize];
+        let threshold =
+            ((volume & VolumeBitFlag::SEEK_TH_MASK) >> VolumeBitFlag::SEEK_TH_SHIFT) as u8;
+
+        // a channel left past the band end by a band or spacing change
+        let start = self.chan.min(last);
+        let mut chan = start;
+        let mut passed = 0;
+        let found = loop {
+            chan = match (up, chan) {
+                (true, chan) if chan >= last => {
+                    if !wrap {
+                        break false;
+                    }
+                    0
+                }
+                (true, chan) => chan + 1,
+                (false, 0) => {
+                    if !wrap {
+                        break false;
+                    }
+                    last
+                }
+                (false, chan) => chan - 1,
+            };
+            passed += 1;
+            if self.rssi(chan) > threshold {
+                break true;
+            }
+            // went around the whole band
+            if chan == start {
+                break false;
+            }
+        };
+        self.chan = chan;
+        self.start(
+            passed.max(1) * SEEK_TIME_PER_CHANNEL_MS,
+            Operation::Seek { found },
+        );
+    }
+
+    fn restart_rds(&mut self) {
+        self.next_group_ms = self.now_ms.wrapping_add(RDS_GROUP_INTERVAL_MS);
+        self.group_index = 0;
+        self.registers[Register::RDA5807M_REG_STATUS as usize] &= !StatusBitFlag::RDSR;
+    }
+
+    fn advance(&mut self, ms: u32) {
+        self.now_ms = self.now_ms.wrapping_add(ms);
+        self.update();
+    }
+
+    // complete operations and deliver RDS groups due by now
+    fn update(&mut self) {
+        if let Some((done_ms, operation)) = self.pending {
+            if self.now_ms.wrapping_sub(done_ms) as i32 >= 0 {
+                self.pending = None;
+                let status = &mut self.registers[Register::RDA5807M_REG_STATUS as usize];
+                *status |= StatusBitFlag::STC;
+                match operation {
+                    Operation::Tune => {
+                        self.registers[Register::RDA5807M_REG_TUNING as usize] &=
+                            !TuningBitFlag::TUNE;
+                    }
+                    Operation::Seek { found } => {
+                        if !found {
+                            *status |= StatusBitFlag::SF;
+                        }
+                        self.registers[Register::RDA5807M_REG_CONFIG as usize] &=
+                            !ConfigBitFlags::SEEK;
+                    }
+                }
+                self.restart_rds();
+            }
+        }
+
+        let config = self.registers[Register::RDA5807M_REG_CONFIG as usize];
+        let rds = self.enabled() && config & ConfigBitFlags::RDS != 0 && self.pending.is_none();
+        while rds && self.now_ms.wrapping_sub(self.next_group_ms) as i32 >= 0 {
+            self.next_group_ms = self.next_group_ms.wrapping_add(RDS_GROUP_INTERVAL_MS);
+            if let Some(group) = self.next_group() {
+                self.registers
+                    [Register::RDA5807M_REG_RDSA as usize..=Register::RDA5807M_REG_RDSD as usize]
+                    .copy_from_slice(&group);
+                self.registers[Register::RDA5807M_REG_STATUS as usize] |= StatusBitFlag::RDSR;
+            }
+        }
+        self.update_status();
+    }
+
+    fn next_group(&mut self) -> Option<Group> {
+        if let Some(group) = self.injected.pop_front() {
+            return Some(group);
+        }
+        let index = self.group_index;
+        let station = self.station(self.chan)?;
+        let group = *station.rds.get(index % station.rds.len().max(1))?;
+        self.group_index = index.wrapping_add(1);
+        Some(group)
+    }
+
+    fn update_status(&mut self) {
+        let config = self.registers[Register::RDA5807M_REG_CONFIG as usize];
+        let station = self.station(self.chan).map(|station| station.stereo);
+        let stereo =
+            station == Some(true) && config & ConfigBitFlags::MONO == 0 && self.pending.is_none();
+        let status = &mut self.registers[Register::RDA5807M_REG_STATUS as usize];
+        *status &= !(StatusBitFlag::ST | StatusBitFlag::READ_CHAN_MASK);
+        *status |= self.chan & StatusBitFlag::READ_CHAN_MASK;
+        if stereo {
+            *status |= StatusBitFlag::ST;
+        }
+
+        let mut rssi = (self.rssi(self.chan) as u16) << RssiBitFlag::RSSI_SHIFT;
+        if self.enabled() {
+            rssi |= RssiBitFlag::FM_READY;
+            if station.is_some() {
+                rssi |= RssiBitFlag::FM_TRUE;
+            }
+        }
+        self.registers[Register::RDA5807M_REG_RSSI as usize] = rssi;
+    }
+
+    fn read(&mut self, register: usize) -> u16 {
+        let value = self.registers[register];
+        // the group is consumed once block D was read
+        if register == Register::RDA5807M_REG_RDSD as usize {
+            self.registers[Register::RDA5807M_REG_STATUS as usize] &= !StatusBitFlag::RDSR;
+        }
+        value
+    }
+}
+
+/// Simulated RDA5807M, see the module documentation
+#[derive(Debug)]
+pub struct SimChip {
+    state: RefCell<State>,
+}
+
+impl Default for SimChip {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SimChip {
+    pub fn new() -> Self {
+        SimChip {
+            state: RefCell::new(State::new()),
+        }
+    }
+
+    /// Add a station to the virtual spectrum, `false` when it is full
+    pub fn add_station(&self, station: SimStation) -> bool {
+        self.state.borrow_mut().stations.push(station).is_ok()
+    }
+
+    pub fn remove_station(&self, frequency: u32) {
+        self.state
+            .borrow_mut()
+            .stations
+            .retain(|station| station.frequency != frequency);
+    }
+
+    /// RSSI of channels without a station
+    pub fn set_noise_rssi(&self, rssi: u8) {
+        self.state.borrow_mut().noise_rssi = rssi;
+    }
+
+    /// Queue a group, delivered before the tuned station's own groups.
+    /// Returns `false` when the queue is full.
+    pub fn inject_rds_group(&self, group: Group) -> bool {
+        self.state.borrow_mut().injected.push_back(group).is_ok()
+    }
+
+    /// Let `ms` milliseconds pass
+    pub fn advance(&self, ms: u32) {
+        self.state.borrow_mut().advance(ms);
+    }
+
+    /// Milliseconds since the chip was created
+    pub fn now_ms(&self) -> u32 {
+        self.state.borrow().now_ms
+    }
+
+    /// Frequency the chip is tuned to (kHz)
+    pub fn frequency(&self) -> u32 {
+        let state = self.state.borrow();
+        state.frequency(state.chan)
+    }
+
+    /// Current value of a register, without the side effects of a bus read
+    pub fn register(&self, register: u8) -> u16 {
+        self.state.borrow().registers[register as usize & 0x0f]
+    }
+
+    /// Delay that advances the chip's clock, for driver calls taking a delay
+    pub fn delay(&self) -> SimDelay<'_> {
+        SimDelay { chip: self }
+    }
+
+    fn check_address(address: u8) -> Result<(), SimError> {
+        if address == DEVICE_BASE_RANDOM_ADDRESS || address == DEVICE_BASE_SEQ_ADDRESS {
+            Ok(())
+        } else {
+            Err(SimError::Nack)
+        }
+    }
+
+    fn write_bytes(&self, address: u8, bytes: &[u8]) -> Result<(), SimError> {
+        Self::check_address(address)?;
+        let [register, data @ ..] = bytes else {
+            return Err(SimError::InvalidTransfer);
+        };
+        let start = *register as usize;
+        if start + data.len() / 2 > REGISTER_COUNT || data.len() % 2 != 0 {
+            return Err(SimError::InvalidTransfer);
+        }
+        let mut state = self.state.borrow_mut();
+        state.update();
+        for (register, word) in (start..).zip(data.chunks_exact(2)) {
+            state.write(register, u16::from_be_bytes([word[0], word[1]]));
+        }
+        state.update();
+        Ok(())
+    }
+
+    fn read_bytes(&self, start: usize, buffer: &mut [u8]) -> Result<(), SimError> {
+        let mut state = self.state.borrow_mut();
+        state.update();
+        for (pos, chunk) in buffer.chunks_mut(2).enumerate() {
+            let word = state.read((start + pos) % REGISTER_COUNT).to_be_bytes();
+            chunk.copy_from_slice(&word[..chunk.len()]);
+        }
+        Ok(())
+    }
+}
+
+impl Write for &SimChip {
+    type Error = SimError;
+
+    fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.write_bytes(address, bytes)
+    }
+}
+
+impl Read for &SimChip {
+    type Error = SimError;
+
+    /// Sequential read, starting at register 0x0A
+    fn read(&mut self, address: u8, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        SimChip::check_address(address)?;
+        self.read_bytes(SEQ_READ_START, buffer)
+    }
+}
+
+impl WriteRead for &SimChip {
+    type Error = SimError;
+
+    fn write_read(
+        &mut self,
+        address: u8,
+        bytes: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        SimChip::check_address(address)?;
+        let [register] = bytes else {
+            return Err(SimError::InvalidTransfer);
+        };
+        if *register as usize >= REGISTER_COUNT {
+            return Err(SimError::InvalidTransfer);
+        }
+        self.read_bytes(*register as usize, buffer)
+    }
+}
+
+impl Write for SimChip {
+    type Error = SimError;
+
+    fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), Self::Error> {
+        (&*self).write(address, bytes)
+    }
+}
+
+impl Read for SimChip {
+    type Error = SimError;
+
+    fn read(&mut self, address: u8, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        (&*self).read(address, buffer)
+    }
+}
+
+impl WriteRead for SimChip {
+    type Error = SimError;
+
+    fn write_read(
+        &mut self,
+        address: u8,
+        bytes: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        (&*self).write_read(address, bytes, buffer)
+    }
+}
+
+/// Delay advancing a `SimChip`'s clock instead of waiting
+#[derive(Debug)]
+pub struct SimDelay<'a> {
+    chip: &'a SimChip,
+}
+
+impl DelayMs<u32> for SimDelay<'_> {
+    fn delay_ms(&mut self, ms: u32) {
+        self.chip.advance(ms);
+    }
+}
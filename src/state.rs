@@ -0,0 +1,164 @@
+use crate::band::{channel_to_frequency, frequency_to_channel, Band, Spacing};
+use crate::register_address::{
+    ConfigBitFlags, Register, StatusBitFlag, TuningBitFlag, VolumeBitFlag,
+};
+use crate::{Error, Rda5708m};
+
+/// Complete chip configuration, as held in registers 0x02 - 0x08
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub struct RadioState {
+    // REG 0x02
+    pub enabled: bool,
+    pub high_impedance: bool,
+    pub mute: bool,
+    pub mono: bool,
+    pub bass: bool,
+    pub rclk_non_calibrate: bool,
+    pub rclk_direct_input: bool,
+    pub seek_up: bool,
+    // stop at the band limit instead of wrapping
+    pub seek_stop_at_limit: bool,
+    // CLK_MODE bits, one of the `ConfigBitFlags::CLK_*` values
+    pub clock: u16,
+    pub rds: bool,
+    pub new_method: bool,
+    // REG 0x03, frequency in kHz
+    pub frequency: u32,
+    pub band: Band,
+    pub spacing: Spacing,
+    // REG 0x04
+    pub gpio: u16,
+    // REG 0x05
+    pub int_mode: bool,
+    pub seek_mode: u8,
+    pub seek_threshold: u8,
+    pub lna_port_sel: u8,
+    pub lna_icsel: u8,
+    pub volume: u8,
+    // REG 0x06
+    pub i2s: u16,
+    // REG 0x07
+    pub blend: u16,
+    // REG 0x08
+    pub freq_direct: u16,
+}
+
+impl RadioState {
+    /// Decode registers 0x02 - 0x08, the channel is taken from `readchan`
+    /// since CHAN in 0x03 is not updated after every seek.
+    pub fn from_registers(registers: &[u16; 7], readchan: u16) -> Self {
+        let [config, tuning, gpio, volume, i2s, blend, freq_direct] = *registers;
+        let band = Band::from_bits(
+            ((tuning & TuningBitFlag::BAND_MASK) >> TuningBitFlag::BAND_SHIFT) as u8,
+        );
+        let spacing = Spacing::from_bits((tuning & TuningBitFlag::SPACE_MASK) as u8);
+        RadioState {
+            enabled: config & ConfigBitFlags::ENABLE != 0,
+            high_impedance: config & ConfigBitFlags::DHIZ == 0,
+            mute: config & ConfigBitFlags::DMUTE == 0,
+            mono: config & ConfigBitFlags::MONO != 0,
+            bass: config & ConfigBitFlags::BASS != 0,
+            rclk_non_calibrate: config & ConfigBitFlags::RCLKNOCAL != 0,
+            rclk_direct_input: config & ConfigBitFlags::RCLKDIRECT != 0,
+            seek_up: config & ConfigBitFlags::SEEKUP != 0,
+            seek_stop_at_limit: config & ConfigBitFlags::SKMODE != 0,
+            clock: config & ConfigBitFlags::CLK_MODE_MASK,
+            rds: config & ConfigBitFlags::RDS != 0,
+            new_method: config & ConfigBitFlags::NEW != 0,
+            frequency: channel_to_frequency(
+                band,
+                spacing,
+                readchan & StatusBitFlag::READ_CHAN_MASK,
+            ),
+            band,
+            spacing,
+            gpio,
+            int_mode: volume & VolumeBitFlag::INT_MODE != 0,
+            seek_mode: ((volume & VolumeBitFlag::SEEK_MODE_MASK) >> VolumeBitFlag::SEEK_MODE_SHIFT)
+                as u8,
+            seek_threshold: ((volume & VolumeBitFlag::SEEK_TH_MASK) >> VolumeBitFlag::SEEK_TH_SHIFT)
+                as u8,
+            lna_port_sel: ((volume & VolumeBitFlag::LNA_PORT_SEL_MASK)
+                >> VolumeBitFlag::LNA_PORT_SEL_SHIFT) as u8,
+            lna_icsel: ((volume & VolumeBitFlag::LNA_ICSEL_BIT_MASK)
+                >> VolumeBitFlag::LNA_ICSEL_BIT_SHIFT) as u8,
+            volume: (volume & VolumeBitFlag::VOLUME_MASK) as u8,
+            i2s,
+            blend,
+            freq_direct,
+        }
+    }
+
+    /// Encode registers 0x02 - 0x08. SEEK, RESET and TUNE are never set.
+    pub fn to_registers(&self) -> [u16; 7] {
+        let flag = |set: bool, bit: u16| if set { bit } else { 0 };
+        let config = flag(self.enabled, ConfigBitFlags::ENABLE)
+            | flag(!self.high_impedance, ConfigBitFlags::DHIZ)
+            | flag(!self.mute, ConfigBitFlags::DMUTE)
+            | flag(self.mono, ConfigBitFlags::MONO)
+            | flag(self.bass, ConfigBitFlags::BASS)
+            | flag(self.rclk_non_calibrate, ConfigBitFlags::RCLKNOCAL)
+            | flag(self.rclk_direct_input, ConfigBitFlags::RCLKDIRECT)
+            | flag(self.seek_up, ConfigBitFlags::SEEKUP)
+            | flag(self.seek_stop_at_limit, ConfigBitFlags::SKMODE)
+            | (self.clock & ConfigBitFlags::CLK_MODE_MASK)
+            | flag(self.rds, ConfigBitFlags::RDS)
+            | flag(self.new_method, ConfigBitFlags::NEW);
+        let chan = frequency_to_channel(self.band, self.spacing, self.frequency);
+        let tuning = (chan << TuningBitFlag::CHAN_SHIFT)
+            | self.band.register_value()
+            | self.spacing.register_value();
+        let volume = flag(self.int_mode, VolumeBitFlag::INT_MODE)
+            | ((self.seek_mode as u16) << VolumeBitFlag::SEEK_MODE_SHIFT)
+                & VolumeBitFlag::SEEK_MODE_MASK
+            | ((self.seek_threshold as u16) << VolumeBitFlag::SEEK_TH_SHIFT)
+                & VolumeBitFlag::SEEK_TH_MASK
+            | ((self.lna_port_sel as u16) << VolumeBitFlag::LNA_PORT_SEL_SHIFT)
+                & VolumeBitFlag::LNA_PORT_SEL_MASK
+            | ((self.lna_icsel as u16) << VolumeBitFlag::LNA_ICSEL_BIT_SHIFT)
+                & VolumeBitFlag::LNA_ICSEL_BIT_MASK
+            | (self.volume as u16) & VolumeBitFlag::VOLUME_MASK;
+        [
+            config,
+            tuning,
+            self.gpio,
+            volume,
+            self.i2s,
+            self.blend,
+            self.freq_direct,
+        ]
+    }
+}
+
+impl<I2C, E> Rda5708m<I2C>
+where
+    I2C: embedded_hal::blocking::i2c::Write<Error = E>
+        + embedded_hal::blocking::i2c::Read<Error = E>
+        + embedded_hal::blocking::i2c::WriteRead<Error = E>,
+{
+    /// Capture the current configuration and tuned frequency
+    pub fn snapshot(&mut self) -> Result<RadioState, Error<E>> {
+        let mut registers = [0; 7];
+        for (register, value) in (Register::RDA5807M_REG_CONFIG..).zip(registers.iter_mut()) {
+            *value = self.read_register(register)?;
+        }
+        let readchan = self.read_register(Register::RDA5807M_REG_STATUS)?;
+        Ok(RadioState::from_registers(&registers, readchan))
+    }
+
+    /// Write a captured state back. The chip is configured first and the
+    /// frequency is tuned last, once band and spacing are in place.
+    pub fn restore(&mut self, state: &RadioState) -> Result<(), Error<E>> {
+        let [config, tuning, gpio, volume, i2s, blend, freq_direct] = state.to_registers();
+        self.write_register(Register::RDA5807M_REG_CONFIG, config)?;
+        self.write_register(Register::RDA5807M_REG_GPIO, gpio)?;
+        self.write_register(Register::RDA5807M_REG_VOLUME, volume)?;
+        self.write_register(Register::RDA5807M_REG_I2S, i2s)?;
+        self.write_register(Register::RDA5807M_REG_BLEND, blend)?;
+        self.write_register(Register::RDA5807M_REG_FREQ, freq_direct)?;
+        self.write_register(Register::RDA5807M_REG_TUNING, tuning | TuningBitFlag::TUNE)
+    }
+}
@@ -0,0 +1,33 @@
+// ufmt has no `uDebug` for `str`, this prints text fields quoted with
+// non-printable characters replaced.
+
+use ufmt::{uDebug, uWrite, Formatter};
+
+pub(crate) struct Text<'a>(pub(crate) &'a [u8]);
+
+impl<'a> From<&'a str> for Text<'a> {
+    fn from(text: &'a str) -> Self {
+        Text(text.as_bytes())
+    }
+}
+
+impl uDebug for Text<'_> {
+    fn fmt<W>(&self, f: &mut Formatter<'_, W>) -> Result<(), W::Error>
+    where
+        W: uWrite + ?Sized,
+    {
+        f.write_char('"')?;
+        for &byte in self.0 {
+            let c = match byte {
+                b'"' | b'\\' => {
+                    f.write_char('\\')?;
+                    byte as char
+                }
+                0x20..=0x7e => byte as char,
+                _ => '?',
+            };
+            f.write_char(c)?;
+        }
+        f.write_char('"')
+    }
+}
@@ -0,0 +1,481 @@
+// I²C wrapper recording every register access of the driver, for debugging
+// in the field:
+//
+//     let mut radio = Rda5708m::new(Traced::<_, 32>::new(i2c), Address::default());
+//     ...
+//     radio.destroy().dump(&mut out)?;
+
+use core::fmt;
+
+use embedded_hal::blocking::i2c::{Read, Write, WriteRead};
+use heapless::HistoryBuffer;
+
+#[cfg(feature = "ufmt")]
+use crate::text::Text;
+
+use crate::register_address::{
+    BlendBitFlag, ConfigBitFlags, GpioBitFlag, Register, RssiBitFlag, StatusBitFlag, TuningBitFlag,
+    VolumeBitFlag,
+};
+
+// sequential reads start at the status register
+const SEQ_READ_START: u8 = Register::RDA5807M_REG_STATUS;
+
+/// Number of registers, 0x00 to 0x0F
+pub const REGISTER_COUNT: usize = 16;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum TraceOp {
+    Read,
+    Write,
+}
+
+/// One register access, `failed` when the bus returned an error
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub struct TraceEntry {
+    pub op: TraceOp,
+    pub register: u8,
+    pub value: u16,
+    pub failed: bool,
+}
+
+/// A decoded part of a register value
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Field {
+    /// Single bit flag that is set
+    Flag(&'static str),
+    /// Multi bit field
+    Value(&'static str, u16),
+}
+
+#[cfg(feature = "ufmt")]
+impl ufmt::uDebug for Field {
+    fn fmt<W>(&self, f: &mut ufmt::Formatter<'_, W>) -> Result<(), W::Error>
+    where
+        W: ufmt::uWrite + ?Sized,
+    {
+        match *self {
+            Field::Flag(name) => f.debug_tuple("Flag")?.field(&Text::from(name))?.finish(),
+            Field::Value(name, value) => f
+                .debug_tuple("Value")?
+                .field(&Text::from(name))?
+                .field(&value)?
+                .finish(),
+        }
+    }
+}
+
+type FieldSpec = (&'static str, u16, u8);
+
+// name, mask and shift of the fields of each register. Single bit fields are
+// listed only when set.
+const CONFIG: &[FieldSpec] = &[
+    ("DHIZ", ConfigBitFlags::DHIZ, 0),
+    ("DMUTE", ConfigBitFlags::DMUTE, 0),
+    ("MONO", ConfigBitFlags::MONO, 0),
+    ("BASS", ConfigBitFlags::BASS, 0),
+    ("RCLKNOCAL", ConfigBitFlags::RCLKNOCAL, 0),
+    ("RCLKDIRECT", ConfigBitFlags::RCLKDIRECT, 0),
+    ("SEEKUP", ConfigBitFlags::SEEKUP, 0),
+    ("SEEK", ConfigBitFlags::SEEK, 0),
+    ("SKMODE", ConfigBitFlags::SKMODE, 0),
+    ("CLK_MODE", ConfigBitFlags::CLK_MODE_MASK, 4),
+    ("RDS", ConfigBitFlags::RDS, 0),
+    ("NEW", ConfigBitFlags::NEW, 0),
+    ("RESET", ConfigBitFlags::RESET, 0),
+    ("ENABLE", ConfigBitFlags::ENABLE, 0),
+];
+const TUNING: &[FieldSpec] = &[
+    ("CHAN", TuningBitFlag::CHAN_MASK, TuningBitFlag::CHAN_SHIFT),
+    ("TUNE", TuningBitFlag::TUNE, 0),
+    ("BAND", TuningBitFlag::BAND_MASK, TuningBitFlag::BAND_SHIFT),
+    ("SPACE", TuningBitFlag::SPACE_MASK, 0),
+];
+const GPIO: &[FieldSpec] = &[
+    ("STCIEN", GpioBitFlag::STCIEN, 0),
+    ("RBDS", GpioBitFlag::RBDS, 0),
+    ("RDS_FIFO_EN", GpioBitFlag::RDS_FIFO_EN, 0),
+    ("DE", GpioBitFlag::DE, 0),
+    ("RDS_FIFO_CLR", GpioBitFlag::RDS_FIFO_CLR, 0),
+    ("SOFTMUTE_EN", GpioBitFlag::SOFTMUTE_EN, 0),
+    ("AFCD", GpioBitFlag::AFCD, 0),
+    ("I2S_ENABLED", GpioBitFlag::I2S_ENABLED, 0),
+    ("GPIO3", GpioBitFlag::GPIO3_MASK, 4),
+    ("GPIO2", GpioBitFlag::GPIO2_MASK, 2),
+    ("GPIO1", GpioBitFlag::GPIO1_MASK, 0),
+];
+const VOLUME: &[FieldSpec] = &[
+    ("INT_MODE", VolumeBitFlag::INT_MODE, 0),
+    (
+        "SEEK_MODE",
+        VolumeBitFlag::SEEK_MODE_MASK,
+        VolumeBitFlag::SEEK_MODE_SHIFT,
+    ),
+    (
+        "SEEK_TH",
+        VolumeBitFlag::SEEK_TH_MASK,
+        VolumeBitFlag::SEEK_TH_SHIFT,
+    ),
+    (
+        "LNA_PORT_SEL",
+        VolumeBitFlag::LNA_PORT_SEL_MASK,
+        VolumeBitFlag::LNA_PORT_SEL_SHIFT,
+    ),
+    (
+        "LNA_ICSEL",
+        VolumeBitFlag::LNA_ICSEL_BIT_MASK,
+        VolumeBitFlag::LNA_ICSEL_BIT_SHIFT,
+    ),
+    ("VOLUME", VolumeBitFlag::VOLUME_MASK, 0),
+];
+const BLEND: &[FieldSpec] = &[
+    (
+        "TH_SOFTBLEND",
+        BlendBitFlag::TH_SOFTBLEND_MASK,
+        BlendBitFlag::TH_SOFTBLEND_SHIFT,
+    ),
+    ("65M_50M_MODE", BlendBitFlag::MODE_65M_50M, 0),
+    ("RDS_VERBOSE", BlendBitFlag::RDS_VERBOSE, 0),
+    (
+        "SEEK_TH_OLD",
+        BlendBitFlag::SEEK_TH_OLD_MASK,
+        BlendBitFlag::SEEK_TH_OLD_SHIFT,
+    ),
+    ("SOFTBLEND_EN", BlendBitFlag::SOFTBLEND_EN, 0),
+    ("FREQ_MODE", BlendBitFlag::FREQ_MODE, 0),
+];
+const STATUS: &[FieldSpec] = &[
+    ("RDSR", StatusBitFlag::RDSR, 0),
+    ("STC", StatusBitFlag::STC, 0),
+    ("SF", StatusBitFlag::SF, 0),
+    ("RDSS", StatusBitFlag::RDDS, 0),
+    ("BLK_E", StatusBitFlag::BLK_E, 0),
+    ("ST", StatusBitFlag::ST, 0),
+    ("READCHAN", StatusBitFlag::READ_CHAN_MASK, 0),
+];
+const RSSI: &[FieldSpec] = &[
+    ("RSSI", RssiBitFlag::RSSI_MASK, RssiBitFlag::RSSI_SHIFT),
+    ("FM_TRUE", RssiBitFlag::FM_TRUE, 0),
+    ("FM_READY", RssiBitFlag::FM_READY, 0),
+    ("ABCD_E", RssiBitFlag::ABCD_E, 0),
+    (
+        "BLERA",
+        RssiBitFlag::BLOCK_ERROR_A_MASK,
+        RssiBitFlag::BLOCK_ERROR_A_SHIFT as u8,
+    ),
+    (
+        "BLERB",
+        RssiBitFlag::BLOCK_ERROR_B_MASK,
+        RssiBitFlag::BLOCK_ERROR_B_SHIFT as u8,
+    ),
+];
+
+/// Name of a register, e.g. "CONFIG" for 0x02
+pub fn register_name(register: u8) -> &'static str {
+    match register {
+        Register::RDA5807M_REG_CHIPID => "CHIPID",
+        Register::RDA5807M_REG_CONFIG => "CONFIG",
+        Register::RDA5807M_REG_TUNING => "TUNING",
+        Register::RDA5807M_REG_GPIO => "GPIO",
+        Register::RDA5807M_REG_VOLUME => "VOLUME",
+        Register::RDA5807M_REG_I2S => "I2S",
+        Register::RDA5807M_REG_BLEND => "BLEND",
+        Register::RDA5807M_REG_FREQ => "FREQ",
+        Register::RDA5807M_REG_STATUS => "STATUS",
+        Register::RDA5807M_REG_RSSI => "RSSI",
+        Register::RDA5807M_REG_RDSA => "RDSA",
+        Register::RDA5807M_REG_RDSB => "RDSB",
+        Register::RDA5807M_REG_RDSC => "RDSC",
+        Register::RDA5807M_REG_RDSD => "RDSD",
+        _ => "RSVD",
+    }
+}
+
+/// The fields of `value` for `register`, named like the bit flag constants
+pub fn fields(register: u8, value: u16) -> impl Iterator<Item = Field> {
+    let specs = match register {
+        Register::RDA5807M_REG_CONFIG => CONFIG,
+        Register::RDA5807M_REG_TUNING => TUNING,
+        Register::RDA5807M_REG_GPIO => GPIO,
+        Register::RDA5807M_REG_VOLUME => VOLUME,
+        Register::RDA5807M_REG_BLEND => BLEND,
+        Register::RDA5807M_REG_STATUS => STATUS,
+        Register::RDA5807M_REG_RSSI => RSSI,
+        _ => &[],
+    };
+    specs.iter().filter_map(move |(name, mask, shift)| {
+        if mask.is_power_of_two() {
+            (value & mask != 0).then_some(Field::Flag(name))
+        } else {
+            Some(Field::Value(name, (value & mask) >> shift))
+        }
+    })
+}
+
+// " NAME" for flags and " NAME=value" for multi bit fields
+fn write_fields(f: &mut fmt::Formatter<'_>, register: u8, value: u16) -> fmt::Result {
+    for field in fields(register, value) {
+        match field {
+            Field::Flag(name) => write!(f, " {}", name)?,
+            Field::Value(name, value) => write!(f, " {}={}", name, value)?,
+        }
+    }
+    Ok(())
+}
+
+#[cfg(feature = "defmt")]
+fn format_fields(f: defmt::Formatter, register: u8, value: u16) {
+    for field in fields(register, value) {
+        match field {
+            Field::Flag(name) => defmt::write!(f, " {=str}", name),
+            Field::Value(name, value) => defmt::write!(f, " {=str}={=u16}", name, value),
+        }
+    }
+}
+
+impl TraceEntry {
+    pub fn fields(&self) -> impl Iterator<Item = Field> {
+        fields(self.register, self.value)
+    }
+}
+
+impl fmt::Display for TraceEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let op = match self.op {
+            TraceOp::Read => 'R',
+            TraceOp::Write => 'W',
+        };
+        write!(
+            f,
+            "{} {}(0x{:02x})",
+            op,
+            register_name(self.register),
+            self.register
+        )?;
+        if self.failed {
+            return f.write_str(" failed");
+        }
+        write!(f, " = 0x{:04x}", self.value)?;
+        write_fields(f, self.register, self.value)
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for TraceEntry {
+    fn format(&self, f: defmt::Formatter) {
+        let op = match self.op {
+            TraceOp::Read => "R",
+            TraceOp::Write => "W",
+        };
+        defmt::write!(
+            f,
+            "{=str} {=str}({=u8:#04x})",
+            op,
+            register_name(self.register),
+            self.register
+        );
+        if self.failed {
+            defmt::write!(f, " failed");
+            return;
+        }
+        defmt::write!(f, " = {=u16:#06x}", self.value);
+        format_fields(f, self.register, self.value);
+    }
+}
+
+/// All registers read at once, see `Rda5708m::read_all_registers`. Displayed
+/// one register per line with its decoded fields:
+///
+/// ```text
+/// 0x02 CONFIG  = 0xc001 DHIZ DMUTE CLK_MODE=0 ENABLE
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub struct RegisterDump {
+    pub registers: [u16; REGISTER_COUNT],
+}
+
+impl RegisterDump {
+    pub fn new(registers: [u16; REGISTER_COUNT]) -> Self {
+        RegisterDump { registers }
+    }
+
+    /// Value of `register`, `None` past 0x0F
+    pub fn get(&self, register: u8) -> Option<u16> {
+        self.registers.get(register as usize).copied()
+    }
+
+    /// Register addresses and values in order
+    pub fn iter(&self) -> impl Iterator<Item = (u8, u16)> + '_ {
+        (0..).zip(self.registers.iter().copied())
+    }
+}
+
+impl From<[u16; REGISTER_COUNT]> for RegisterDump {
+    fn from(registers: [u16; REGISTER_COUNT]) -> Self {
+        RegisterDump::new(registers)
+    }
+}
+
+impl fmt::Display for RegisterDump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (register, value) in self.iter() {
+            write!(
+                f,
+                "0x{:02x} {:<7} = 0x{:04x}",
+                register,
+                register_name(register),
+                value
+            )?;
+            write_fields(f, register, value)?;
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for RegisterDump {
+    fn format(&self, f: defmt::Formatter) {
+        for (register, value) in self.iter() {
+            defmt::write!(
+                f,
+                "{=u8:#04x} {=str} = {=u16:#06x}",
+                register,
+                register_name(register),
+                value
+            );
+            format_fields(f, register, value);
+            defmt::write!(f, "\n");
+        }
+    }
+}
+
+/// I²C bus wrapper keeping the last `N` register accesses
+pub struct Traced<I2C, const N: usize> {
+    i2c: I2C,
+    entries: HistoryBuffer<TraceEntry, N>,
+}
+
+impl<I2C, const N: usize> Traced<I2C, N> {
+    pub fn new(i2c: I2C) -> Self {
+        Traced {
+            i2c,
+            entries: HistoryBuffer::new(),
+        }
+    }
+
+    /// Recorded accesses, oldest first
+    pub fn entries(&self) -> impl Iterator<Item = &TraceEntry> {
+        self.entries.oldest_ordered()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Write the recorded accesses, one per line
+    pub fn dump<W: fmt::Write>(&self, out: &mut W) -> fmt::Result {
+        for entry in self.entries() {
+            writeln!(out, "{}", entry)?;
+        }
+        Ok(())
+    }
+
+    pub fn inner(&self) -> &I2C {
+        &self.i2c
+    }
+
+    pub fn inner_mut(&mut self) -> &mut I2C {
+        &mut self.i2c
+    }
+
+    pub fn into_inner(self) -> I2C {
+        self.i2c
+    }
+
+    // registers `start..` accessed with the 16 bit words in `bytes`, the
+    // register address wraps after 0xFF
+    fn record(&mut self, op: TraceOp, start: u8, bytes: &[u8], failed: bool) {
+        for (i, word) in bytes.chunks_exact(2).enumerate() {
+            self.entries.write(TraceEntry {
+                op,
+                register: start.wrapping_add(i as u8),
+                value: if failed {
+                    0
+                } else {
+                    u16::from_be_bytes([word[0], word[1]])
+                },
+                failed,
+            });
+        }
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl<I2C, const N: usize> defmt::Format for Traced<I2C, N> {
+    fn format(&self, f: defmt::Formatter) {
+        for entry in self.entries() {
+            defmt::write!(f, "{}\n", entry);
+        }
+    }
+}
+
+#[cfg(feature = "ufmt")]
+impl<I2C, const N: usize> ufmt::uDebug for Traced<I2C, N> {
+    fn fmt<W>(&self, f: &mut ufmt::Formatter<'_, W>) -> Result<(), W::Error>
+    where
+        W: ufmt::uWrite + ?Sized,
+    {
+        f.debug_list()?.entries(self.entries())?.finish()
+    }
+}
+
+impl<I2C, const N: usize> fmt::Debug for Traced<I2C, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.entries()).finish()
+    }
+}
+
+impl<I2C: Write, const N: usize> Write for Traced<I2C, N> {
+    type Error = I2C::Error;
+
+    fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), Self::Error> {
+        let result = self.i2c.write(address, bytes);
+        if let [register, data @ ..] = bytes {
+            self.record(TraceOp::Write, *register, data, result.is_err());
+        }
+        result
+    }
+}
+
+impl<I2C: Read, const N: usize> Read for Traced<I2C, N> {
+    type Error = I2C::Error;
+
+    fn read(&mut self, address: u8, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        let result = self.i2c.read(address, buffer);
+        self.record(TraceOp::Read, SEQ_READ_START, buffer, result.is_err());
+        result
+    }
+}
+
+impl<I2C: WriteRead, const N: usize> WriteRead for Traced<I2C, N> {
+    type Error = I2C::Error;
+
+    fn write_read(
+        &mut self,
+        address: u8,
+        bytes: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        let result = self.i2c.write_read(address, bytes, buffer);
+        if let [register, ..] = bytes {
+            self.record(TraceOp::Read, *register, buffer, result.is_err());
+        }
+        result
+    }
+}
@@ -0,0 +1,207 @@
+use crate::rds::RdsDecoder;
+use crate::state::RadioState;
+use crate::{Error, Rda5708m};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub struct TrafficConfig {
+    /// Volume during announcements, `None` keeps the current volume
+    pub volume: Option<u8>,
+    /// Unmute for announcements
+    pub unmute: bool,
+    /// Retune to linked networks (EON) announcing traffic
+    pub follow_eon: bool,
+    /// Return to the normal program after this long even if TA is still set
+    pub max_duration_ms: u32,
+}
+
+impl Default for TrafficConfig {
+    fn default() -> Self {
+        TrafficConfig {
+            volume: Some(10),
+            unmute: true,
+            follow_eon: true,
+            max_duration_ms: 5 * 60 * 1000,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub enum TrafficEvent {
+    /// Announcement started, `retuned` when switched to the linked network `pi`
+    Started { pi: u16, retuned: bool },
+    /// Announcement ended and the previous state was restored
+    Ended { timed_out: bool },
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+struct Announcement {
+    saved: RadioState,
+    pi: u16,
+    // PI of the linked network tuned to, `None` for the current station
+    eon_pi: Option<u16>,
+    elapsed_ms: u32,
+}
+
+/// TP/TA policy: raises the volume (and retunes for EON) while a traffic
+/// announcement is on air and restores the previous state afterwards.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "ufmt", derive(ufmt::derive::uDebug))]
+pub struct TrafficAnnouncer {
+    config: TrafficConfig,
+    active: Option<Announcement>,
+    // PI of the network that timed out, ignored until its TA drops
+    suppressed: Option<u16>,
+}
+
+impl TrafficAnnouncer {
+    pub fn new(config: TrafficConfig) -> Self {
+        TrafficAnnouncer {
+            config,
+            active: None,
+            suppressed: None,
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active.is_some()
+    }
+
+    /// Check the decoder state and act on it. Call after feeding new groups
+    /// to `decoder`, `elapsed_ms` is the time since the previous call.
+    pub fn poll<I2C, E>(
+        &mut self,
+        radio: &mut Rda5708m<I2C>,
+        decoder: &mut RdsDecoder,
+        elapsed_ms: u32,
+    ) -> Result<Option<TrafficEvent>, Error<E>>
+    where
+        I2C: embedded_hal::blocking::i2c::Write<Error = E>
+            + embedded_hal::blocking::i2c::Read<Error = E>
+            + embedded_hal::blocking::i2c::WriteRead<Error = E>,
+    {
+        match self.active.as_mut() {
+            Some(announcement) => {
+                announcement.elapsed_ms = announcement.elapsed_ms.saturating_add(elapsed_ms);
+                let timed_out = announcement.elapsed_ms >= self.config.max_duration_ms;
+                let ended = match announcement.eon_pi {
+                    None => !decoder.ta(),
+                    // until the linked network's own groups arrive TA is unknown
+                    Some(pi) => decoder.pi() == Some(pi) && !decoder.ta(),
+                };
+                if !timed_out && !ended {
+                    return Ok(None);
+                }
+                let pi = announcement.pi;
+                self.finish(radio, decoder)?;
+                if timed_out {
+                    self.suppressed = Some(pi);
+                }
+                Ok(Some(TrafficEvent::Ended { timed_out }))
+            }
+            None => {
+                if let Some(pi) = self.suppressed {
+                    // TA of a network missing from the decoder is unknown
+                    let ta = if decoder.pi() == Some(pi) {
+                        Some(decoder.ta())
+                    } else {
+                        decoder.eon().get(pi).map(|network| network.ta)
+                    };
+                    if ta == Some(false) {
+                        self.suppressed = None;
+                    }
+                }
+                let suppressed = self.suppressed;
+                let own = decoder.tp()
+                    && decoder.ta()
+                    && (suppressed.is_none() || decoder.pi() != suppressed);
+                let linked = self
+                    .config
+                    .follow_eon
+                    .then(|| {
+                        decoder.eon().networks().iter().find(|network| {
+                            network.tp && network.ta && Some(network.pi) != suppressed
+                        })
+                    })
+                    .flatten();
+                if !own && linked.is_none() {
+                    return Ok(None);
+                }
+
+                let target = if own {
+                    None
+                } else {
+                    let tuned = radio.get_frequency()?;
+                    let target = linked.and_then(|network| {
+                        network.frequency_for(tuned).map(|freq| (network.pi, freq))
+                    });
+                    match target {
+                        Some(target) => Some(target),
+                        // no frequency known for the linked network
+                        None => return Ok(None),
+                    }
+                };
+                let saved = radio.snapshot()?;
+                let pi = match target {
+                    None => decoder.pi().unwrap_or_default(),
+                    Some((pi, freq)) => {
+                        radio.set_frequency(freq)?;
+                        decoder.reset();
+                        pi
+                    }
+                };
+                if let Some(volume) = self.config.volume {
+                    radio.set_volume(volume.max(saved.volume))?;
+                }
+                if self.config.unmute {
+                    radio.mute(false)?;
+                }
+                self.active = Some(Announcement {
+                    saved,
+                    pi,
+                    eon_pi: target.map(|(pi, _)| pi),
+                    elapsed_ms: 0,
+                });
+                Ok(Some(TrafficEvent::Started {
+                    pi,
+                    retuned: target.is_some(),
+                }))
+            }
+        }
+    }
+
+    fn finish<I2C, E>(
+        &mut self,
+        radio: &mut Rda5708m<I2C>,
+        decoder: &mut RdsDecoder,
+    ) -> Result<(), Error<E>>
+    where
+        I2C: embedded_hal::blocking::i2c::Write<Error = E>
+            + embedded_hal::blocking::i2c::Read<Error = E>
+            + embedded_hal::blocking::i2c::WriteRead<Error = E>,
+    {
+        let Some(announcement) = self.active.take() else {
+            return Ok(());
+        };
+        if announcement.eon_pi.is_some() {
+            radio.restore(&announcement.saved)?;
+            decoder.reset();
+        } else {
+            radio.set_volume(announcement.saved.volume)?;
+            radio.mute(announcement.saved.mute)?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for TrafficAnnouncer {
+    fn default() -> Self {
+        Self::new(TrafficConfig::default())
+    }
+}
@@ -0,0 +1,64 @@
+use rda5807m::rds::charset::{decode, decode_into, encode, from_char, to_char};
+
+#[test]
+fn every_assigned_code_round_trips() {
+    for byte in (0x00..=0xfe).filter(|byte| *byte != 0x7f) {
+        assert_eq!(from_char(to_char(byte)), Some(byte), "{byte:#04x}");
+    }
+}
+
+#[test]
+fn standard_table() {
+    let expected = [
+        (0x24, '¤'),
+        (0x41, 'A'),
+        (0x5e, '―'),
+        (0x60, '‖'),
+        (0x7e, '¯'),
+        (0x80, 'á'),
+        (0x8d, 'ß'),
+        (0x91, 'ä'),
+        (0x97, 'ö'),
+        (0x99, 'ü'),
+        (0xa9, '€'),
+        (0xab, '$'),
+        (0xbf, '§'),
+        (0xc2, 'É'),
+        (0xd1, 'Ä'),
+        (0xd7, 'Ö'),
+        (0xd9, 'Ü'),
+        (0xe7, 'Ø'),
+        (0xf1, 'å'),
+        (0xfe, 'ŧ'),
+    ];
+    for (byte, c) in expected {
+        assert_eq!(to_char(byte), c, "{byte:#04x}");
+    }
+    assert_eq!(to_char(0x7f), ' ');
+    assert_eq!(to_char(0xff), ' ');
+    assert_eq!(from_char('^'), None);
+    assert_eq!(from_char('ß'), Some(0x8d));
+    assert_eq!(from_char('β'), None);
+}
+
+#[test]
+fn can_decode_station_names() {
+    let ps = [b'B', b'a', b'y', b'e', b'r', b'n', b' ', 0x33];
+    assert_eq!(decode::<32>(&ps).as_str(), "Bayern 3");
+    let ps = [b'F', b'r', b'a', b'n', b'c', b'e', b' ', 0x82];
+    assert_eq!(decode::<32>(&ps).as_str(), "France é");
+    let ps = [b'M', 0x97, b'w', b'e'];
+    let mut buf = [0; 16];
+    assert_eq!(decode_into(&ps, &mut buf), "Möwe");
+    // truncated on a character boundary
+    let mut buf = [0; 2];
+    assert_eq!(decode_into(&ps, &mut buf), "M");
+    assert_eq!(decode::<3>(&ps).as_str(), "Mö");
+}
+
+#[test]
+fn can_encode_text() {
+    let mut buf = [0; 8];
+    assert_eq!(encode("Köln ß^", &mut buf, b'?'), 7);
+    assert_eq!(&buf[..7], &[b'K', 0x97, b'l', b'n', b' ', 0x8d, b'?']);
+}
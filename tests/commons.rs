@@ -1,3 +1,5 @@
+#![allow(dead_code)]
+
 use embedded_hal_mock::eh0::i2c::{Mock as I2cMock, Transaction as I2cTransaction};
 use rda5807m::{Address, Rda5708m};
 
@@ -12,3 +14,20 @@ pub fn new(expectations: &[I2cTransaction]) -> Rda5708m<I2cMock> {
 pub fn destroy(rda5807m: Rda5708m<I2cMock>) {
     rda5807m.destroy().done();
 }
+
+// Expect a read of `register` returning `value`
+pub fn read(register: u8, value: u16) -> I2cTransaction {
+    I2cTransaction::write_read(
+        DEVICE_BASE_RANDOM_ADDRESS,
+        vec![register],
+        vec![(value >> 8) as u8, value as u8],
+    )
+}
+
+// Expect `value` to be written to `register`
+pub fn write(register: u8, value: u16) -> I2cTransaction {
+    I2cTransaction::write(
+        DEVICE_BASE_RANDOM_ADDRESS,
+        vec![register, (value >> 8) as u8, value as u8],
+    )
+}
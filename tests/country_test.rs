@@ -0,0 +1,54 @@
+use rda5807m::rds::country::country_code;
+use rda5807m::rds::slc::ProgramItem;
+use rda5807m::rds::{GroupType, GroupVersion, RdsDecoder};
+
+const PI: u16 = 0xd318;
+
+fn group_1a(c: u16, d: u16) -> [u16; 4] {
+    [PI, GroupType::new(1, GroupVersion::A).block_b_bits(), c, d]
+}
+
+#[test]
+fn can_decode_slow_labelling_codes() {
+    let mut decoder = RdsDecoder::new();
+    assert_eq!(decoder.country(), None);
+
+    // ECC 0xe0, PIN 16th 18:30
+    decoder.decode(&group_1a(0x00e0, 16 << 11 | 18 << 6 | 30));
+    // language 0x08 (German)
+    decoder.decode(&group_1a(0x3008, 0));
+    // TMC id
+    decoder.decode(&group_1a(0x1123, 0));
+
+    assert_eq!(decoder.ecc(), Some(0xe0));
+    assert_eq!(decoder.country(), Some("DE"));
+    let slc = decoder.slow_labelling();
+    assert_eq!(slc.language(), Some(0x08));
+    assert_eq!(slc.tmc_id(), Some(0x123));
+    assert!(!slc.linkage_actuator());
+    // the last group carried no PIN
+    assert_eq!(decoder.pin(), None);
+
+    decoder.decode(&group_1a(0x00e0, 16 << 11 | 18 << 6 | 30));
+    assert_eq!(
+        decoder.pin(),
+        Some(ProgramItem {
+            day: 16,
+            hour: 18,
+            minute: 30
+        })
+    );
+}
+
+#[test]
+fn can_map_countries() {
+    assert_eq!(country_code(0xd318, 0xe0), Some("DE"));
+    assert_eq!(country_code(0xc201, 0xe1), Some("GB"));
+    assert_eq!(country_code(0xf201, 0xe1), Some("FR"));
+    assert_eq!(country_code(0x1234, 0xa0), Some("US"));
+    assert_eq!(country_code(0xc123, 0xa1), Some("CA"));
+    assert_eq!(country_code(0x9123, 0xf2), Some("PH"));
+    assert_eq!(country_code(0xd318, 0xe4), None);
+    assert_eq!(country_code(0x0318, 0xe0), None);
+    assert_eq!(country_code(0xd318, 0x42), None);
+}
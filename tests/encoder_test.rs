@@ -0,0 +1,192 @@
+use rda5807m::rds::clock::ClockTime;
+use rda5807m::rds::encoder::{InjectedErrors, RdsEncoder, RdsStation};
+use rda5807m::rds::rtplus::{RtPlusContentType, RtPlusTag};
+use rda5807m::rds::sync::{BlockQuality, BlockSync, Confidence, BLOCK_BITS};
+use rda5807m::rds::{GroupVersion, RdsDecoder};
+
+const PI: u16 = 0xd318;
+
+fn station() -> RdsStation {
+    let mut station = RdsStation::new(PI, b"RADIO 1 ");
+    station.pty = 10;
+    station.tp = true;
+    station.music = true;
+    station.radiotext = heapless::Vec::from_slice(b"Now playing: Song by Artist").unwrap();
+    station.afs = heapless::Vec::from_slice(&[97_000, 101_500, 88_100]).unwrap();
+    station.ptyn = Some(*b"Pop Hits");
+    station.ecc = Some(0xe0);
+    station.clock = Some(ClockTime {
+        year: 2024,
+        month: 5,
+        day: 1,
+        hour: 18,
+        minute: 30,
+        offset: 4,
+    });
+    station.rt_plus = heapless::Vec::from_slice(&[
+        RtPlusTag {
+            content_type: RtPlusContentType::ItemTitle,
+            start: 13,
+            length: 4,
+        },
+        RtPlusTag {
+            content_type: RtPlusContentType::ItemArtist,
+            start: 21,
+            length: 6,
+        },
+    ])
+    .unwrap();
+    station
+}
+
+fn decode_rotations(encoder: &mut RdsEncoder, decoder: &mut RdsDecoder, rotations: usize) {
+    for _ in 0..encoder.rotation_len() * rotations {
+        decoder.decode(&encoder.next_group());
+    }
+}
+
+#[test]
+fn round_trips_station() {
+    let mut encoder = RdsEncoder::new(station());
+    let mut decoder = RdsDecoder::new();
+    decode_rotations(&mut encoder, &mut decoder, 1);
+
+    assert_eq!(decoder.pi(), Some(PI));
+    assert_eq!(decoder.pty(), 10);
+    assert!(decoder.tp());
+    assert!(!decoder.ta());
+    assert!(decoder.music());
+    assert_eq!(decoder.ps(), Some(b"RADIO 1 "));
+    assert_eq!(decoder.rt(), Some(&b"Now playing: Song by Artist"[..]));
+    assert_eq!(decoder.af_list().frequencies(), [97_000, 101_500, 88_100]);
+    assert!(decoder.af_list().is_complete());
+    assert_eq!(decoder.ptyn(), Some(b"Pop Hits"));
+    assert_eq!(decoder.ecc(), Some(0xe0));
+    assert_eq!(decoder.clock_time(), encoder.station().clock);
+    assert_eq!(
+        decoder.rt_plus_text(RtPlusContentType::ItemTitle),
+        Some(&b"Song"[..])
+    );
+    assert_eq!(
+        decoder.rt_plus_text(RtPlusContentType::ItemArtist),
+        Some(&b"Artist"[..])
+    );
+
+    encoder.station_mut().ta = true;
+    decode_rotations(&mut encoder, &mut decoder, 1);
+    assert!(decoder.ta());
+}
+
+#[test]
+fn round_trips_radiotext_versions() {
+    let mut station = RdsStation::new(PI, b"RADIO 1 ");
+    station.radiotext = heapless::Vec::from_slice(b"Hello").unwrap();
+    station.rt_version = GroupVersion::B;
+    let mut encoder = RdsEncoder::new(station);
+    let mut decoder = RdsDecoder::new();
+    decode_rotations(&mut encoder, &mut decoder, 1);
+    assert_eq!(decoder.radiotext().capacity(), 32);
+    assert_eq!(decoder.rt(), Some(&b"Hello"[..]));
+
+    // a new text with the A/B flag toggled replaces the old one
+    let station = encoder.station_mut();
+    station.rt_version = GroupVersion::A;
+    station.rt_ab = true;
+    station.radiotext = heapless::Vec::from_slice(b"Next").unwrap();
+    decode_rotations(&mut encoder, &mut decoder, 1);
+    assert_eq!(decoder.radiotext().ab_flag(), Some(true));
+    assert_eq!(decoder.rt(), Some(&b"Next"[..]));
+}
+
+#[test]
+fn corrects_injected_errors() {
+    let mut encoder = RdsEncoder::new(station());
+    encoder.set_errors(Some(InjectedErrors {
+        interval: 2,
+        bits: [0b1, 0b11 << 7, 0, 0b1 << 20],
+    }));
+    let mut decoder = RdsDecoder::new();
+    let mut corrected = 0;
+    for _ in 0..encoder.rotation_len() {
+        let group = encoder.next_corrected_group();
+        if group.confidence() == Confidence::Corrected {
+            assert_eq!(group.quality[1], BlockQuality::Corrected(2));
+            corrected += 1;
+        }
+        decoder.decode_corrected(&group).unwrap();
+    }
+    assert_eq!(corrected, encoder.rotation_len() / 2);
+    assert_eq!(decoder.ps(), Some(b"RADIO 1 "));
+}
+
+#[test]
+fn drops_uncorrectable_groups() {
+    let mut encoder = RdsEncoder::new(RdsStation::new(PI, b"RADIO 1 "));
+    encoder.set_errors(Some(InjectedErrors {
+        interval: 4,
+        bits: [0, 0b1000_0000_0001, 0, 0],
+    }));
+    let mut decoder = RdsDecoder::new();
+    for _ in 0..8 {
+        let group = encoder.next_corrected_group();
+        let _ = decoder.decode_corrected(&group);
+    }
+    // segment 3 was damaged both times
+    assert_eq!(decoder.ps(), None);
+    assert_eq!(&decoder.ps_raw()[..6], b"RADIO ");
+}
+
+#[test]
+fn feeds_block_sync() {
+    let mut encoder = RdsEncoder::new(station());
+    let mut sync = BlockSync::default();
+    let mut decoder = RdsDecoder::new();
+    for _ in 0..encoder.rotation_len() + 1 {
+        for block in encoder.next_blocks() {
+            for bit in (0..BLOCK_BITS).rev() {
+                if let Some(group) = sync.push_bit(block & (1 << bit) != 0) {
+                    decoder.decode_corrected(&group);
+                }
+            }
+        }
+    }
+    assert!(sync.is_synced());
+    assert_eq!(decoder.ps(), Some(b"RADIO 1 "));
+    assert_eq!(decoder.af_list().frequencies().len(), 3);
+}
+
+#[test]
+fn skips_invalid_clock_times() {
+    let clock = |year, month, day| ClockTime {
+        year,
+        month,
+        day,
+        hour: 12,
+        minute: 0,
+        offset: 2,
+    };
+    assert_eq!(clock(1970, 1, 1).mjd(), Some(40_587));
+    assert_eq!(clock(2217, 9, 27).mjd(), Some(0x1_ffff));
+    assert_eq!(clock(2217, 9, 28).mjd(), None);
+    assert_eq!(clock(1969, 12, 31).mjd(), None);
+    assert_eq!(clock(0, 1, 1).mjd(), None);
+    assert_eq!(clock(2023, 2, 29).mjd(), None);
+    assert_eq!(clock(2024, 13, 1).mjd(), None);
+    assert_eq!(clock(2024, 1, 0).mjd(), None);
+    assert_eq!(
+        ClockTime {
+            minute: 60,
+            ..clock(2024, 1, 1)
+        }
+        .blocks(),
+        None
+    );
+
+    let mut station = RdsStation::new(PI, b"RADIO 1 ");
+    station.clock = Some(clock(0, 2, 1));
+    let mut encoder = RdsEncoder::new(station);
+    assert_eq!(&encoder.clock_group()[1..], &[0x4000, 0, 0]);
+    let mut decoder = RdsDecoder::new();
+    decode_rotations(&mut encoder, &mut decoder, 1);
+    assert_eq!(decoder.clock_time(), None);
+}
@@ -0,0 +1,64 @@
+use rda5807m::rds::{Event, GroupType, GroupVersion, RdsDecoder};
+
+const PI_TN: u16 = 0xd318;
+const PI_ON: u16 = 0xd312;
+
+fn eon_a(variant: u16, c: u16) -> [u16; 4] {
+    let b = GroupType::new(14, GroupVersion::A).block_b_bits() | 1 << 4 | variant;
+    [PI_TN, b, c, PI_ON]
+}
+
+fn eon_b(ta: bool) -> [u16; 4] {
+    let b = GroupType::new(14, GroupVersion::B).block_b_bits() | 1 << 4 | (ta as u16) << 3;
+    [PI_TN, b, PI_TN, PI_ON]
+}
+
+#[test]
+fn can_decode_other_networks() {
+    let mut decoder = RdsDecoder::new();
+    for (variant, chars) in [b"BA", b"YE", b"RN", b" 3"].iter().enumerate() {
+        decoder.decode(&eon_a(variant as u16, u16::from_be_bytes(**chars)));
+    }
+    // AF 89.9MHz and 97.3MHz
+    decoder.decode(&eon_a(4, 24 << 8 | 98));
+    // while tuned to 90.0MHz the other network is on 93.1MHz
+    decoder.decode(&eon_a(5, 25 << 8 | 56));
+    // PTY 10, TA off
+    decoder.decode(&eon_a(13, 10 << 11));
+
+    let network = decoder.eon().get(PI_ON).unwrap();
+    assert_eq!(network.ps(), Some(b"BAYERN 3"));
+    assert_eq!(network.afs.as_slice(), &[89_900, 97_300]);
+    assert_eq!(network.frequency_for(90_000), Some(93_100));
+    assert_eq!(network.frequency_for(100_000), Some(89_900));
+    assert_eq!(network.pty, 10);
+    assert!(network.tp);
+    assert!(!network.ta);
+    assert_eq!(decoder.eon().networks().len(), 1);
+    assert_eq!(decoder.poll_event(), None);
+}
+
+#[test]
+fn emits_traffic_announcement_events() {
+    let mut decoder = RdsDecoder::new();
+    decoder.decode(&eon_b(true));
+    decoder.decode(&eon_b(true));
+    assert_eq!(
+        decoder.poll_event(),
+        Some(Event::EonTrafficAnnouncement {
+            pi: PI_ON,
+            active: true
+        })
+    );
+    assert_eq!(decoder.poll_event(), None);
+    assert!(decoder.eon().get(PI_ON).unwrap().ta);
+
+    decoder.decode(&eon_b(false));
+    assert_eq!(
+        decoder.poll_event(),
+        Some(Event::EonTrafficAnnouncement {
+            pi: PI_ON,
+            active: false
+        })
+    );
+}
@@ -0,0 +1,157 @@
+use embedded_hal_mock::eh0::delay::NoopDelay;
+use rda5807m::register_address::{ConfigBitFlags, Register, StatusBitFlag, TuningBitFlag};
+use rda5807m::{Band, Direction, Error, Preset, Spacing};
+
+use crate::commons::{destroy, new, read, write};
+
+mod commons;
+
+const CONFIG: u16 = ConfigBitFlags::DHIZ | ConfigBitFlags::DMUTE | ConfigBitFlags::ENABLE;
+const SEEK_UP: u16 = CONFIG | ConfigBitFlags::SEEKUP | ConfigBitFlags::SEEK;
+
+#[test]
+fn displays_errors() {
+    let cases: [(Error<u8>, &str); 8] = [
+        (Error::I2C(5), "I²C bus error: 5"),
+        (Error::ReadOnlyRegister(0x0a), "register 0x0a is read only"),
+        (
+            Error::InvalidArgument("volume above 15"),
+            "invalid argument: volume above 15",
+        ),
+        (
+            Error::FrequencyOutOfBand(118_900),
+            "118900 kHz is outside the selected band",
+        ),
+        (Error::Timeout, "tune or seek did not complete in time"),
+        (Error::SeekFailed, "seek found no station"),
+        (
+            Error::WrongChipId(0x1234),
+            "wrong chip id 0x1234, expected 0x5804",
+        ),
+        (Error::NotPowered, "the chip is not powered up"),
+    ];
+    for (error, text) in cases {
+        assert_eq!(error.to_string(), text);
+    }
+    let error: Box<dyn std::error::Error> = Box::new(Error::I2C(5u8));
+    assert_eq!(error.to_string(), "I²C bus error: 5");
+}
+
+#[test]
+fn rejects_invalid_arguments() {
+    let tuning = TuningBitFlag::BAND_87_108_MHZ | TuningBitFlag::SPACE_100_KHZ;
+    let expectations = [read(Register::RDA5807M_REG_TUNING, tuning)];
+    let mut dev = new(&expectations);
+    assert!(matches!(dev.set_volume(16), Err(Error::InvalidArgument(_))));
+    assert!(matches!(
+        dev.set_seek_threshold(16),
+        Err(Error::InvalidArgument(_))
+    ));
+    assert!(matches!(
+        dev.set_frequency(118_900),
+        Err(Error::FrequencyOutOfBand(118_900))
+    ));
+    let preset = Preset {
+        frequency: 101_700,
+        band: Band::Japan,
+        spacing: Spacing::Khz100,
+        name: *b"        ",
+        volume: 5,
+    };
+    assert!(matches!(
+        dev.apply_preset(&preset),
+        Err(Error::FrequencyOutOfBand(101_700))
+    ));
+    destroy(dev);
+}
+
+#[test]
+fn rejects_frequencies_beyond_last_channel() {
+    // 76 - 108MHz at 25kHz ends at channel 0x3ff, 101.575MHz
+    let tuning = TuningBitFlag::BAND_76_108_MHZ | TuningBitFlag::SPACE_25_KHZ;
+    let expectations = [
+        read(Register::RDA5807M_REG_TUNING, tuning),
+        read(Register::RDA5807M_REG_TUNING, tuning),
+        write(
+            Register::RDA5807M_REG_TUNING,
+            tuning | 0x3ff << TuningBitFlag::CHAN_SHIFT | TuningBitFlag::TUNE,
+        ),
+    ];
+    let mut dev = new(&expectations);
+    assert!(matches!(
+        dev.set_frequency(105_000),
+        Err(Error::FrequencyOutOfBand(105_000))
+    ));
+    dev.set_frequency(101_575).unwrap();
+    let preset = Preset {
+        frequency: 101_600,
+        band: Band::WorldWide,
+        spacing: Spacing::Khz25,
+        name: *b"        ",
+        volume: 5,
+    };
+    assert!(matches!(
+        dev.apply_preset(&preset),
+        Err(Error::FrequencyOutOfBand(101_600))
+    ));
+    destroy(dev);
+}
+
+#[test]
+fn verifies_chip_id() {
+    let expectations = [
+        read(Register::RDA5807M_REG_CHIPID, 0x5804),
+        read(Register::RDA5807M_REG_CHIPID, 0x5801),
+    ];
+    let mut dev = new(&expectations);
+    dev.verify_id().unwrap();
+    assert!(matches!(dev.verify_id(), Err(Error::WrongChipId(0x5801))));
+    destroy(dev);
+}
+
+#[test]
+fn seek_needs_power() {
+    let expectations = [read(Register::RDA5807M_REG_CONFIG, ConfigBitFlags::DHIZ)];
+    let mut dev = new(&expectations);
+    assert!(matches!(dev.seek_up(true), Err(Error::NotPowered)));
+    destroy(dev);
+}
+
+#[test]
+fn seek_reports_station_failure_and_timeout() {
+    let tuning = TuningBitFlag::BAND_87_108_MHZ | TuningBitFlag::SPACE_100_KHZ;
+    let mut expectations = vec![
+        // finds 101.7MHz
+        read(Register::RDA5807M_REG_CONFIG, CONFIG),
+        write(Register::RDA5807M_REG_CONFIG, SEEK_UP),
+        read(Register::RDA5807M_REG_STATUS, StatusBitFlag::STC | 147),
+        read(Register::RDA5807M_REG_TUNING, tuning),
+        // nothing found
+        read(Register::RDA5807M_REG_CONFIG, CONFIG),
+        write(Register::RDA5807M_REG_CONFIG, SEEK_UP),
+        read(
+            Register::RDA5807M_REG_STATUS,
+            StatusBitFlag::STC | StatusBitFlag::SF | 147,
+        ),
+        // never completes: polled at 0, 5 and 10ms
+        read(Register::RDA5807M_REG_CONFIG, CONFIG),
+        write(Register::RDA5807M_REG_CONFIG, SEEK_UP),
+    ];
+    for _ in 0..3 {
+        expectations.push(read(Register::RDA5807M_REG_STATUS, 0));
+    }
+    let mut dev = new(&expectations);
+    assert_eq!(
+        dev.seek(Direction::Up, true, &mut NoopDelay, 10).unwrap(),
+        101_700
+    );
+    assert!(matches!(
+        dev.seek(Direction::Up, true, &mut NoopDelay, 10),
+        Err(Error::SeekFailed)
+    ));
+    assert!(matches!(
+        dev.seek(Direction::Up, true, &mut NoopDelay, 10),
+        Err(Error::Timeout)
+    ));
+    destroy(dev);
+}
@@ -0,0 +1,202 @@
+use rda5807m::presets::{PresetError, RECORD_SIZE};
+use rda5807m::register_address::{Register, TuningBitFlag};
+use rda5807m::{Band, Preset, Presets, Spacing};
+
+use crate::commons::{destroy, new, read, write};
+
+mod commons;
+
+fn preset() -> Preset {
+    Preset {
+        frequency: 89_100,
+        band: Band::Japan,
+        spacing: Spacing::Khz50,
+        name: *b"NHK FM  ",
+        volume: 7,
+    }
+}
+
+#[test]
+fn can_save_and_load_presets() {
+    let mut presets = Presets::<4>::new();
+    assert!(presets.set(1, preset()));
+    assert!(!presets.set(4, preset()));
+
+    let mut storage = [0u8; Presets::<4>::STORAGE_SIZE];
+    presets.save(&mut storage[..]).unwrap();
+    let loaded = Presets::<4>::load(&mut storage[..]).unwrap();
+    assert_eq!(loaded, presets);
+    assert_eq!(loaded.get(0), None);
+    assert_eq!(loaded.get(1), Some(&preset()));
+
+    assert_eq!(
+        Presets::<8>::load(&mut storage[..]),
+        Err(PresetError::CapacityMismatch(4))
+    );
+    storage[RECORD_SIZE * 2 + 3] ^= 0x01;
+    assert_eq!(
+        Presets::<4>::load(&mut storage[..]),
+        Err(PresetError::Checksum)
+    );
+}
+
+#[test]
+fn can_save_and_load_largest_capacity() {
+    let mut presets = Presets::<255>::new();
+    assert!(presets.set(254, preset()));
+    let mut storage = vec![0u8; Presets::<255>::STORAGE_SIZE];
+    presets.save(&mut storage[..]).unwrap();
+    assert_eq!(storage[5], 255);
+    assert_eq!(Presets::<255>::load(&mut storage[..]).unwrap(), presets);
+    assert_eq!(
+        Presets::<254>::load(&mut storage[..]),
+        Err(PresetError::CapacityMismatch(255))
+    );
+}
+
+#[test]
+fn blank_storage_has_no_presets() {
+    let mut storage = [0xffu8; Presets::<2>::STORAGE_SIZE];
+    assert_eq!(
+        Presets::<2>::load(&mut storage[..]),
+        Err(PresetError::BadMagic)
+    );
+}
+
+#[test]
+fn can_recall_preset() {
+    let mut presets = Presets::<2>::new();
+    presets.set(0, preset());
+    // 89.1MHz is channel 262 of 76-91MHz at 50kHz
+    let tuning = 262 << TuningBitFlag::CHAN_SHIFT
+        | TuningBitFlag::TUNE
+        | TuningBitFlag::BAND_76_91_MHZ
+        | TuningBitFlag::SPACE_50_KHZ;
+    let expectations = [
+        read(Register::RDA5807M_REG_TUNING, 0),
+        write(Register::RDA5807M_REG_TUNING, tuning),
+        read(Register::RDA5807M_REG_VOLUME, 0x8880),
+        write(Register::RDA5807M_REG_VOLUME, 0x8887),
+    ];
+    let mut dev = new(&expectations);
+    assert!(dev.recall_preset(&presets, 0).unwrap());
+    assert!(!dev.recall_preset(&presets, 1).unwrap());
+    destroy(dev);
+}
+
+#[cfg(feature = "embedded-storage")]
+mod nor_flash {
+    use embedded_storage::nor_flash::{ErrorType, NorFlash, NorFlashErrorKind, ReadNorFlash};
+    use rda5807m::presets::NorFlashStorage;
+    use rda5807m::Presets;
+
+    use super::preset;
+
+    const ERASE_SIZE: usize = 256;
+    const OFFSET: u32 = ERASE_SIZE as u32;
+
+    // 1KiB of NOR flash: erasing sets whole sectors to 0xFF, writes only
+    // clear bits
+    struct MockFlash {
+        data: [u8; 4 * ERASE_SIZE],
+        erased: Vec<(u32, u32)>,
+    }
+
+    impl MockFlash {
+        fn new() -> Self {
+            MockFlash {
+                data: [0; 4 * ERASE_SIZE],
+                erased: Vec::new(),
+            }
+        }
+    }
+
+    impl ErrorType for MockFlash {
+        type Error = NorFlashErrorKind;
+    }
+
+    impl ReadNorFlash for MockFlash {
+        const READ_SIZE: usize = 1;
+
+        fn read(&mut self, offset: u32, bytes: &mut [u8]) -> Result<(), Self::Error> {
+            let offset = offset as usize;
+            let src = self
+                .data
+                .get(offset..offset + bytes.len())
+                .ok_or(NorFlashErrorKind::OutOfBounds)?;
+            bytes.copy_from_slice(src);
+            Ok(())
+        }
+
+        fn capacity(&self) -> usize {
+            self.data.len()
+        }
+    }
+
+    impl NorFlash for MockFlash {
+        const WRITE_SIZE: usize = 16;
+        const ERASE_SIZE: usize = ERASE_SIZE;
+
+        fn erase(&mut self, from: u32, to: u32) -> Result<(), Self::Error> {
+            if !(from as usize).is_multiple_of(ERASE_SIZE)
+                || !(to as usize).is_multiple_of(ERASE_SIZE)
+            {
+                return Err(NorFlashErrorKind::NotAligned);
+            }
+            self.data
+                .get_mut(from as usize..to as usize)
+                .ok_or(NorFlashErrorKind::OutOfBounds)?
+                .fill(0xff);
+            self.erased.push((from, to));
+            Ok(())
+        }
+
+        fn write(&mut self, offset: u32, bytes: &[u8]) -> Result<(), Self::Error> {
+            let offset = offset as usize;
+            if !offset.is_multiple_of(Self::WRITE_SIZE)
+                || !bytes.len().is_multiple_of(Self::WRITE_SIZE)
+            {
+                return Err(NorFlashErrorKind::NotAligned);
+            }
+            let dst = self
+                .data
+                .get_mut(offset..offset + bytes.len())
+                .ok_or(NorFlashErrorKind::OutOfBounds)?;
+            for (dst, byte) in dst.iter_mut().zip(bytes) {
+                *dst &= byte;
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn can_save_and_load_presets() {
+        let mut presets = Presets::<4>::new();
+        presets.set(2, preset());
+        let mut storage = NorFlashStorage::new(MockFlash::new(), OFFSET);
+        presets.save(&mut storage).unwrap();
+        // saved twice, so the second save relies on the erase
+        presets.set(0, preset());
+        presets.save(&mut storage).unwrap();
+        assert_eq!(Presets::<4>::load(&mut storage).unwrap(), presets);
+
+        let flash = storage.destroy();
+        // 80 bytes of presets round up to one sector
+        const { assert!(Presets::<4>::STORAGE_SIZE < ERASE_SIZE) };
+        assert_eq!(flash.erased, [(OFFSET, OFFSET + ERASE_SIZE as u32); 2]);
+        // the sectors around the preset area are untouched
+        assert!(flash.data[..ERASE_SIZE].iter().all(|byte| *byte == 0));
+        assert!(flash.data[2 * ERASE_SIZE..].iter().all(|byte| *byte == 0));
+    }
+
+    #[test]
+    fn erases_whole_sectors() {
+        // 17 slots take 288 bytes, two sectors
+        let presets = Presets::<17>::new();
+        let mut storage = NorFlashStorage::new(MockFlash::new(), OFFSET);
+        presets.save(&mut storage).unwrap();
+        assert_eq!(Presets::<17>::load(&mut storage).unwrap(), presets);
+        let flash = storage.destroy();
+        assert_eq!(flash.erased, [(OFFSET, OFFSET + 2 * ERASE_SIZE as u32)]);
+    }
+}
@@ -0,0 +1,125 @@
+use proptest::prelude::*;
+use rda5807m::band::{channel_to_frequency, frequency_to_channel, last_channel};
+use rda5807m::rds::clock::ClockTime;
+use rda5807m::rds::rbds::{call_sign_to_pi, pi_to_call_sign, CallSign};
+use rda5807m::rds::GroupType;
+use rda5807m::register_address::{StatusRegister, VolumeRegister};
+use rda5807m::{Band, Spacing};
+
+fn band() -> impl Strategy<Value = Band> {
+    (0u8..4).prop_map(Band::from_bits)
+}
+
+fn spacing() -> impl Strategy<Value = Spacing> {
+    (0u8..4).prop_map(Spacing::from_bits)
+}
+
+// a band, spacing and a channel inside the band
+fn channel() -> impl Strategy<Value = (Band, Spacing, u16)> {
+    (band(), spacing()).prop_flat_map(|(band, spacing)| {
+        (Just(band), Just(spacing), 0..=last_channel(band, spacing))
+    })
+}
+
+proptest! {
+    #[test]
+    fn volume_register_round_trips(data: u16) {
+        prop_assert_eq!(VolumeRegister::from_u16(data).to_u16(), data);
+    }
+
+    #[test]
+    fn volume_fields_round_trip(
+        int_mode: bool,
+        seek_mode in 0u8..4,
+        rsvd: bool,
+        seek_th in 0u8..16,
+        lna_port_sel in 0u8..4,
+        lna_icsel in 0u8..4,
+        volume in 0u8..16,
+    ) {
+        let register = VolumeRegister {
+            int_mode,
+            seek_mode,
+            rsvd,
+            seek_th,
+            lna_port_sel,
+            lna_icsel,
+            volume,
+        };
+        prop_assert_eq!(VolumeRegister::from_u16(register.to_u16()), register);
+    }
+
+    #[test]
+    fn status_register_round_trips(data: u16) {
+        prop_assert_eq!(StatusRegister::from_u16(data).to_u16(), data);
+    }
+
+    #[test]
+    fn group_type_round_trips(block_b: u16) {
+        let group_type = GroupType::from_block_b(block_b);
+        prop_assert_eq!(group_type.block_b_bits(), block_b & 0xf800);
+        prop_assert_eq!(GroupType::new(group_type.code, group_type.version), group_type);
+    }
+
+    #[test]
+    fn channel_math_round_trips((band, spacing, chan) in channel()) {
+        let freq = channel_to_frequency(band, spacing, chan);
+        prop_assert!(freq >= band.start_khz() && freq <= band.end_khz());
+        prop_assert_eq!(frequency_to_channel(band, spacing, freq), chan);
+    }
+
+    #[test]
+    fn clock_time_round_trips(
+        days in 0u32..60_000,
+        hour in 0u8..24,
+        minute in 0u8..60,
+        offset in -15i8..=15,
+    ) {
+        // MJD 40587 is 1970-01-01
+        let b = ((40_587 + days) >> 15) as u16;
+        let c = ((40_587 + days) << 1) as u16 | (hour >> 4) as u16;
+        let d = ((hour & 0xf) as u16) << 12
+            | (minute as u16) << 6
+            | if offset < 0 { 1 << 5 } else { 0 }
+            | offset.unsigned_abs() as u16;
+        let clock = ClockTime::from_blocks(b, c, d).unwrap();
+        prop_assert_eq!(clock.mjd(), Some(40_587 + days));
+        prop_assert_eq!((clock.hour, clock.minute, clock.offset), (hour, minute, offset));
+        prop_assert_eq!(clock.blocks(), Some((b, c, d)));
+    }
+
+    #[test]
+    fn call_sign_round_trips(call_sign in "[KW][A-Z]{3}") {
+        let pi = call_sign_to_pi(&call_sign).unwrap();
+        // x0yz and xy00 are never sent as is
+        prop_assert!(pi & 0x0f00 != 0 && pi & 0x00ff != 0);
+        prop_assert_eq!(
+            pi_to_call_sign(pi),
+            Some(CallSign::Letters(call_sign.as_str().try_into().unwrap()))
+        );
+    }
+}
+
+#[cfg(feature = "sim")]
+mod sim {
+    use proptest::prelude::*;
+    use rda5807m::band::channel_to_frequency;
+    use rda5807m::sim::{SimChip, TUNE_TIME_MS};
+    use rda5807m::{Address, Rda5708m};
+
+    use super::channel;
+
+    proptest! {
+        #[test]
+        fn set_then_get_frequency_is_identity((band, spacing, chan) in channel()) {
+            let chip = SimChip::new();
+            let mut radio = Rda5708m::new(&chip, Address::default());
+            radio.start().unwrap();
+            radio.set_band_and_spacing(band, spacing).unwrap();
+            let freq = channel_to_frequency(band, spacing, chan);
+            radio.set_frequency(freq).unwrap();
+            chip.advance(TUNE_TIME_MS);
+            prop_assert_eq!(radio.get_frequency().unwrap(), freq);
+        }
+    }
+}
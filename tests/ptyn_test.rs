@@ -0,0 +1,70 @@
+use rda5807m::rds::{GroupType, GroupVersion, RdsDecoder};
+
+const PI: u16 = 0xa201;
+
+fn ptyn(ab: bool, segment: u16, text: &[u8; 4]) -> [u16; 4] {
+    let b = GroupType::new(10, GroupVersion::A).block_b_bits() | (ab as u16) << 4 | segment;
+    [
+        PI,
+        b,
+        u16::from_be_bytes([text[0], text[1]]),
+        u16::from_be_bytes([text[2], text[3]]),
+    ]
+}
+
+fn long_ps(text: &[u8]) -> Vec<[u16; 4]> {
+    let b = GroupType::new(15, GroupVersion::A).block_b_bits();
+    text.chunks(4)
+        .enumerate()
+        .map(|(segment, chars)| {
+            let mut bytes = [b' '; 4];
+            bytes[..chars.len()].copy_from_slice(chars);
+            [
+                PI,
+                b | segment as u16,
+                u16::from_be_bytes([bytes[0], bytes[1]]),
+                u16::from_be_bytes([bytes[2], bytes[3]]),
+            ]
+        })
+        .collect()
+}
+
+#[test]
+fn can_decode_program_type_name() {
+    let mut decoder = RdsDecoder::new();
+    decoder.decode(&ptyn(false, 1, b"ball"));
+    assert_eq!(decoder.ptyn(), None);
+    decoder.decode(&ptyn(false, 0, b"Foot"));
+    assert_eq!(decoder.ptyn(), Some(b"Football"));
+
+    // A/B toggle clears the name
+    decoder.decode(&ptyn(true, 0, b"Tenn"));
+    assert_eq!(decoder.ptyn(), None);
+    assert_eq!(decoder.ptyn_state().raw(), b"Tenn\0\0\0\0");
+    decoder.decode(&ptyn(true, 1, b"is  "));
+    assert_eq!(decoder.ptyn(), Some(b"Tennis  "));
+}
+
+#[test]
+fn can_decode_long_ps() {
+    let mut decoder = RdsDecoder::new();
+    let name = "Ö1 Österreich\r".as_bytes();
+    let groups = long_ps(name);
+    for group in &groups[1..] {
+        decoder.decode(group);
+    }
+    assert!(!decoder.long_ps_state().is_complete());
+    assert_eq!(decoder.long_ps(), None);
+    decoder.decode(&groups[0]);
+    assert_eq!(decoder.long_ps(), Some("Ö1 Österreich"));
+
+    // the complete name is kept while a changed one is received
+    let groups = long_ps("Radio Wien 89,9\r".as_bytes());
+    decoder.decode(&groups[0]);
+    decoder.decode(&groups[1]);
+    assert_eq!(decoder.long_ps(), Some("Ö1 Österreich"));
+    for group in &groups[2..] {
+        decoder.decode(group);
+    }
+    assert_eq!(decoder.long_ps(), Some("Radio Wien 89,9"));
+}
@@ -0,0 +1,67 @@
+use rda5807m::rds::pty::pty_name;
+use rda5807m::rds::rbds::{call_sign_to_pi, pi_to_call_sign, CallSign};
+use rda5807m::rds::{GroupType, GroupVersion, RdsDecoder};
+
+fn letters(call_sign: &str) -> Option<CallSign> {
+    Some(CallSign::Letters(call_sign.try_into().unwrap()))
+}
+
+#[test]
+fn can_convert_four_letter_call_signs() {
+    let known = [
+        // 0x1000 and 0x3e00 are sent as AF10 and AF3E
+        (0xaf10, "KAAA"),
+        (0xaf3e, "KRKY"),
+        // 0x10b5 is sent as A1B5
+        (0xa1b5, "KAGZ"),
+        (0x54a7, "KZZZ"),
+        (0x54a8, "WAAA"),
+        (0x994f, "WZZZ"),
+        (0x3aab, "KQED"),
+        (0x64a1, "WGBH"),
+    ];
+    for (pi, call_sign) in known {
+        assert_eq!(pi_to_call_sign(pi), letters(call_sign), "{pi:#06x}");
+        assert_eq!(call_sign_to_pi(call_sign), Some(pi), "{call_sign}");
+    }
+    assert_eq!(call_sign_to_pi("kqed"), Some(0x3aab));
+}
+
+#[test]
+fn can_convert_three_letter_call_signs() {
+    assert_eq!(pi_to_call_sign(0x9950), letters("KEX"));
+    assert_eq!(pi_to_call_sign(0x9972), letters("WGN"));
+    assert_eq!(pi_to_call_sign(0x9989), letters("WWL"));
+    assert_eq!(call_sign_to_pi("WGN"), Some(0x9972));
+    // unassigned code inside the three letter range
+    assert_eq!(pi_to_call_sign(0x9961), None);
+}
+
+#[test]
+fn handles_exceptions() {
+    // AFxx is xx00, Axyz is x0yz
+    assert_eq!(pi_to_call_sign(0xaf3e), pi_to_call_sign(0x3e00));
+    assert_eq!(pi_to_call_sign(0xa3b5), pi_to_call_sign(0x30b5));
+    assert_eq!(pi_to_call_sign(0xb012), Some(CallSign::Linked(0x12)));
+    assert_eq!(pi_to_call_sign(0xe0ff), Some(CallSign::Linked(0xff)));
+    assert_eq!(pi_to_call_sign(0x0fff), None);
+    assert_eq!(call_sign_to_pi("CBC"), None);
+    assert_eq!(call_sign_to_pi("K1AB"), None);
+}
+
+#[test]
+fn decoder_uses_rbds_pty_table() {
+    let b = GroupType::new(0, GroupVersion::A).block_b_bits() | 5 << 5;
+    let group = [0x3aab, b, 0, 0x2020];
+    let mut decoder = RdsDecoder::new();
+    decoder.decode(&group);
+    assert_eq!(decoder.pty_name(), "Education");
+    assert_eq!(decoder.call_sign(), None);
+
+    decoder.set_rbds(true);
+    assert_eq!(decoder.pty_name(), "Rock");
+    assert_eq!(decoder.call_sign(), letters("KQED"));
+    decoder.reset();
+    assert!(decoder.rbds());
+    assert_eq!(pty_name(31, true), "Emergency");
+}
@@ -1,7 +1,7 @@
 use embedded_hal_mock::eh0::i2c::Transaction as I2cTransaction;
 
-use crate::commons::{destroy, new, DEVICE_BASE_RANDOM_ADDRESS};
-use crate::register_address::{Register, TuningBitFlag};
+use crate::commons::{destroy, new, read, write, DEVICE_BASE_RANDOM_ADDRESS};
+use crate::register_address::{ConfigBitFlags, Register, TuningBitFlag};
 
 mod commons;
 mod register_address;
@@ -90,7 +90,7 @@ fn can_read_freq() {
 
 #[test]
 fn can_set_freq() {
-    let tuning_bit = 0x13f << TuningBitFlag::CHAN_SHIFT
+    let tuning_bit = 0xc8 << TuningBitFlag::CHAN_SHIFT
         | TuningBitFlag::TUNE
         | TuningBitFlag::BAND_87_108_MHZ
         | TuningBitFlag::SPACE_100_KHZ;
@@ -110,6 +110,29 @@ fn can_set_freq() {
         ),
     ];
     let mut dev = new(&expectations);
-    dev.set_frequency(118_900).unwrap();
+    dev.set_frequency(107_000).unwrap();
+    destroy(dev);
+}
+
+#[test]
+fn can_mute() {
+    let config = ConfigBitFlags::DHIZ | ConfigBitFlags::ENABLE;
+    let expectations = [
+        // DMUTE cleared mutes the output
+        read(
+            Register::RDA5807M_REG_CONFIG,
+            config | ConfigBitFlags::DMUTE,
+        ),
+        write(Register::RDA5807M_REG_CONFIG, config),
+        // DMUTE set is normal output
+        read(Register::RDA5807M_REG_CONFIG, config),
+        write(
+            Register::RDA5807M_REG_CONFIG,
+            config | ConfigBitFlags::DMUTE,
+        ),
+    ];
+    let mut dev = new(&expectations);
+    dev.mute(true).unwrap();
+    dev.mute(false).unwrap();
     destroy(dev);
 }
@@ -0,0 +1,86 @@
+use rda5807m::register_address::{BlendBitFlag, GpioBitFlag, Register, RssiBitFlag, StatusBitFlag};
+use rda5807m::{RdsFifoGroup, RdsMode};
+
+use crate::commons::{destroy, new, read, write};
+
+mod commons;
+
+#[test]
+fn can_set_rds_mode() {
+    let expectations = [
+        read(Register::RDA5807M_REG_GPIO, GpioBitFlag::DE),
+        write(
+            Register::RDA5807M_REG_GPIO,
+            GpioBitFlag::DE
+                | GpioBitFlag::RBDS
+                | GpioBitFlag::RDS_FIFO_EN
+                | GpioBitFlag::RDS_FIFO_CLR,
+        ),
+        read(Register::RDA5807M_REG_BLEND, 0x4000),
+        write(
+            Register::RDA5807M_REG_BLEND,
+            0x4000 | BlendBitFlag::RDS_VERBOSE,
+        ),
+        read(
+            Register::RDA5807M_REG_GPIO,
+            GpioBitFlag::DE | GpioBitFlag::RBDS | GpioBitFlag::RDS_FIFO_EN,
+        ),
+        read(
+            Register::RDA5807M_REG_BLEND,
+            0x4000 | BlendBitFlag::RDS_VERBOSE,
+        ),
+    ];
+    let mut dev = new(&expectations);
+    let mode = RdsMode {
+        verbose: true,
+        fifo: true,
+        rbds: true,
+    };
+    dev.set_rds_mode(mode).unwrap();
+    assert_eq!(dev.get_rds_mode().unwrap(), mode);
+    destroy(dev);
+}
+
+#[test]
+fn can_drain_rds_fifo() {
+    let synced = StatusBitFlag::RDSR | StatusBitFlag::RDDS;
+    let expectations = [
+        read(Register::RDA5807M_REG_STATUS, synced),
+        read(Register::RDA5807M_REG_RSSI, 0x6000),
+        read(Register::RDA5807M_REG_RDSA, 0xd318),
+        read(Register::RDA5807M_REG_RDSB, 0x0408),
+        read(Register::RDA5807M_REG_RDSC, 0xe0cd),
+        read(Register::RDA5807M_REG_RDSD, 0x4241),
+        read(Register::RDA5807M_REG_STATUS, synced | StatusBitFlag::BLK_E),
+        read(Register::RDA5807M_REG_RSSI, 0x6000 | RssiBitFlag::ABCD_E),
+        read(Register::RDA5807M_REG_RDSA, 0x1234),
+        read(Register::RDA5807M_REG_RDSB, 0x5678),
+        read(Register::RDA5807M_REG_RDSC, 0x9abc),
+        read(Register::RDA5807M_REG_RDSD, 0xdef0),
+        read(Register::RDA5807M_REG_STATUS, StatusBitFlag::RDDS),
+    ];
+    let mut dev = new(&expectations);
+    let groups = dev.drain_rds_fifo::<4>().unwrap();
+    assert_eq!(
+        groups.as_slice(),
+        &[
+            RdsFifoGroup {
+                blocks: [0xd318, 0x0408, 0xe0cd, 0x4241],
+                block_e: false,
+                synced: true,
+                blera: 0,
+                blerb: 0,
+            },
+            RdsFifoGroup {
+                blocks: [0x1234, 0x5678, 0x9abc, 0xdef0],
+                block_e: true,
+                synced: true,
+                blera: 0,
+                blerb: 0,
+            },
+        ]
+    );
+    assert!(groups[0].group().is_some());
+    assert_eq!(groups[1].group(), None);
+    destroy(dev);
+}
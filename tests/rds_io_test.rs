@@ -0,0 +1,92 @@
+#![cfg(feature = "std")]
+
+use std::io::Cursor;
+use std::time::{Duration, UNIX_EPOCH};
+
+use rda5807m::rds::io::{replay, HexReader, HexRecord, HexWriter};
+use rda5807m::rds::sync::{BlockQuality, CorrectedGroup};
+use rda5807m::rds::RdsDecoder;
+
+// RDS Spy log of a station sending PS "RADIO 1 "
+const CAPTURE: &str = "<recorder=RDS Spy>
+<date=2024/05/01 18:30:12>
+
+D318 0408 E0CD 5241 @2024/05/01 18:30:12.25
+D318 0409 E0CD 4449
+D318 ---- E0CD 4f20 @2024/05/01 18:30:12.43
+D318 040A E0CD 4F20 @2024/05/01 18:30:12.52 # bler 0 1 0 0
+D318 040B E0CD 3120
+";
+
+#[test]
+fn writes_hex_groups() {
+    let mut writer = HexWriter::new(Vec::new());
+    let clean = CorrectedGroup::from_block_errors([0xd318, 0x0408, 0xe0cd, 0x5241], 0, 0);
+    let timestamp = UNIX_EPOCH + Duration::from_millis(1_714_588_212_250);
+    writer.write_group(&clean, Some(timestamp)).unwrap();
+    let damaged = CorrectedGroup::from_block_errors([0xd318, 0x0409, 0xe0cd, 0x4449], 2, 3);
+    writer.write_group(&damaged, None).unwrap();
+
+    assert_eq!(
+        String::from_utf8(writer.into_inner()).unwrap(),
+        "D318 0408 E0CD 5241 @2024/05/01 18:30:12.25\n\
+         D318 ---- E0CD 4449 # bler 2 3 0 0\n"
+    );
+}
+
+#[test]
+fn reads_what_was_written() {
+    let groups = [
+        CorrectedGroup::from_block_errors([0x1234, 0x2000, 0x4142, 0x4344], 1, 0),
+        CorrectedGroup::from_block_errors([0x1234, 0x0000, 0x0000, 0x4546], 3, 0),
+    ];
+    let timestamp = UNIX_EPOCH + Duration::from_millis(951_825_600_990);
+    let mut writer = HexWriter::new(Vec::new());
+    for group in &groups {
+        writer.write_group(group, Some(timestamp)).unwrap();
+    }
+
+    let text = writer.into_inner();
+    let records: Vec<HexRecord> = HexReader::new(Cursor::new(&text))
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].group, groups[0]);
+    assert_eq!(records[0].timestamp, Some(timestamp));
+    // uncorrectable blocks are not written
+    assert_eq!(records[1].group.blocks, [0, 0, 0, 0x4546]);
+    assert_eq!(records[1].group.quality[0], BlockQuality::Uncorrectable);
+}
+
+#[test]
+fn replays_capture_into_decoder() {
+    let mut decoder = RdsDecoder::new();
+    assert_eq!(replay(Cursor::new(CAPTURE), &mut decoder).unwrap(), 4);
+    assert_eq!(decoder.pi(), Some(0xd318));
+    assert_eq!(decoder.ps(), Some(b"RADIO 1 "));
+}
+
+#[test]
+fn reports_bad_lines() {
+    let error = HexReader::new(Cursor::new("D318 0408 E0CD 5241\nD318 04G8 E0CD\n"))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    assert!(error.to_string().starts_with("line 2:"));
+}
+
+#[test]
+fn rejects_out_of_range_timestamps() {
+    for timestamp in ["300000000000/01/01 00:00:00.00", "10000/01/01 00:00:00.00"] {
+        let line = format!("D318 0408 E0CD 5241 @{timestamp}\n");
+        let error = HexReader::new(Cursor::new(line))
+            .next()
+            .unwrap()
+            .unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        assert!(error.to_string().contains("invalid timestamp"));
+    }
+    let line = "D318 0408 E0CD 5241 @9999/12/31 23:59:59.99\n";
+    let record = HexReader::new(Cursor::new(line)).next().unwrap().unwrap();
+    assert!(record.timestamp.is_some());
+}
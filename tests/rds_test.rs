@@ -0,0 +1,155 @@
+use rda5807m::rds::rtplus::{RtPlusContentType, RT_PLUS_AID};
+use rda5807m::rds::{GroupType, GroupVersion, RdsDecoder};
+
+const PI: u16 = 0xd318;
+
+fn block_b(code: u8, version: GroupVersion, low: u16) -> u16 {
+    // TP set, PTY 10 (pop music)
+    GroupType::new(code, version).block_b_bits() | 1 << 10 | 10 << 5 | low
+}
+
+fn ps_groups(ps: &[u8; 8]) -> Vec<[u16; 4]> {
+    (0..4)
+        .map(|segment| {
+            let d = (ps[segment * 2] as u16) << 8 | ps[segment * 2 + 1] as u16;
+            [PI, block_b(0, GroupVersion::A, segment as u16), 0xe0cd, d]
+        })
+        .collect()
+}
+
+fn rt_groups(text: &[u8], ab: bool) -> Vec<[u16; 4]> {
+    text.chunks(4)
+        .enumerate()
+        .map(|(segment, chars)| {
+            let mut chars = chars.to_vec();
+            chars.resize(4, b' ');
+            let low = (ab as u16) << 4 | segment as u16;
+            [
+                PI,
+                block_b(2, GroupVersion::A, low),
+                (chars[0] as u16) << 8 | chars[1] as u16,
+                (chars[2] as u16) << 8 | chars[3] as u16,
+            ]
+        })
+        .collect()
+}
+
+#[test]
+fn can_decode_ps() {
+    let mut decoder = RdsDecoder::new();
+    let groups = ps_groups(b"RADIO 1 ");
+    for group in &groups[..3] {
+        decoder.decode(group);
+        assert_eq!(decoder.ps(), None);
+    }
+    assert_eq!(
+        decoder.decode(&groups[3]),
+        GroupType::new(0, GroupVersion::A)
+    );
+    assert_eq!(decoder.ps(), Some(b"RADIO 1 "));
+    assert_eq!(decoder.pi(), Some(PI));
+    assert_eq!(decoder.pty(), 10);
+    assert!(decoder.tp());
+
+    // another station resets everything
+    decoder.decode(&[0x1234, block_b(0, GroupVersion::A, 0), 0, 0x4142]);
+    assert_eq!(decoder.pi(), Some(0x1234));
+    assert_eq!(decoder.ps(), None);
+}
+
+#[test]
+fn can_decode_radiotext() {
+    let mut decoder = RdsDecoder::new();
+    let text = b"Now playing\r";
+    for group in rt_groups(text, false) {
+        decoder.decode(&group);
+    }
+    assert_eq!(decoder.rt(), Some(&b"Now playing"[..]));
+    assert_eq!(decoder.radiotext().ab_flag(), Some(false));
+
+    // the A/B flag toggles for a new text
+    let groups = rt_groups(b"Traffic news", true);
+    decoder.decode(&groups[0]);
+    assert_eq!(decoder.rt(), None);
+    assert_eq!(&decoder.radiotext().raw()[..4], b"Traf");
+}
+
+#[test]
+fn can_decode_version_b_radiotext() {
+    let mut decoder = RdsDecoder::new();
+    let b = block_b(2, GroupVersion::B, 0);
+    decoder.decode(&[PI, b, PI, u16::from_be_bytes(*b"Hi")]);
+    decoder.decode(&[PI, b | 1, PI, u16::from_be_bytes([b'!', 0x0d])]);
+    assert_eq!(decoder.rt(), Some(&b"Hi!"[..]));
+}
+
+// decoder with RadioText "Queen - Bohemian Rhapsody" and its RT+ artist and title tags
+fn tagged_radiotext() -> RdsDecoder {
+    let mut decoder = RdsDecoder::new();
+    // RT+ carried in group 11A
+    decoder.decode(&[
+        PI,
+        block_b(3, GroupVersion::A, 11 << 1),
+        0x0000,
+        RT_PLUS_AID,
+    ]);
+    assert_eq!(
+        decoder.oda_group(RT_PLUS_AID),
+        Some(GroupType::new(11, GroupVersion::A))
+    );
+
+    let text = b"Queen - Bohemian Rhapsody\r";
+    for group in rt_groups(text, false) {
+        decoder.decode(&group);
+    }
+    // artist: start 0, length 5; title: start 8, length 17
+    let (artist, title) = (RtPlusContentType::ItemArtist.code() as u16, 1u16);
+    let b = block_b(11, GroupVersion::A, 1 << 4 | 1 << 3 | artist >> 3);
+    let c = (artist & 0b111) << 13 | (5 - 1) << 1 | title >> 5;
+    let d = (title & 0x1f) << 11 | 8 << 5 | (17 - 1);
+    decoder.decode(&[PI, b, c, d]);
+    decoder
+}
+
+#[test]
+fn can_decode_rt_plus() {
+    let mut decoder = tagged_radiotext();
+    assert!(decoder.rt_plus().item_running());
+    assert_eq!(
+        decoder.rt_plus_text(RtPlusContentType::ItemArtist),
+        Some(&b"Queen"[..])
+    );
+    assert_eq!(
+        decoder.rt_plus_text(RtPlusContentType::ItemTitle),
+        Some(&b"Bohemian Rhapsody"[..])
+    );
+    assert_eq!(decoder.rt_plus_text(RtPlusContentType::ItemAlbum), None);
+
+    // new RadioText invalidates the tags
+    decoder.decode(&rt_groups(b"Next up", true)[0]);
+    assert!(decoder.rt_plus().tags().is_empty());
+    assert_eq!(decoder.rt_plus_text(RtPlusContentType::ItemTitle), None);
+}
+
+#[test]
+fn rt_plus_tags_end_with_radiotext_version() {
+    let mut decoder = tagged_radiotext();
+    // same A/B flag, but a 2B text replaces the 2A one
+    let b = block_b(2, GroupVersion::B, 0);
+    decoder.decode(&[PI, b, PI, u16::from_be_bytes(*b"Hi")]);
+    assert!(decoder.rt_plus().tags().is_empty());
+    assert_eq!(decoder.rt_plus_text(RtPlusContentType::ItemArtist), None);
+}
+
+#[test]
+fn rt_plus_content_type_codes() {
+    for code in 0..64 {
+        assert_eq!(RtPlusContentType::from_u8(code).code(), code);
+    }
+    assert_eq!(RtPlusContentType::from_u8(4), RtPlusContentType::ItemArtist);
+    assert_eq!(
+        RtPlusContentType::from_u8(31),
+        RtPlusContentType::StationNameShort
+    );
+    assert_eq!(RtPlusContentType::from_u8(63), RtPlusContentType::GetData);
+}
@@ -0,0 +1,68 @@
+use rda5807m::register_address::Register;
+use rda5807m::trace::REGISTER_COUNT;
+use rda5807m::{Error, RegisterDump};
+
+use crate::commons::{destroy, new, read, write};
+
+mod commons;
+
+#[test]
+fn reads_all_registers() {
+    let expectations: Vec<_> = (0..REGISTER_COUNT as u8)
+        .map(|register| read(register, 0x1100 + register as u16))
+        .collect();
+    let mut rda5807m = new(&expectations);
+    let registers = rda5807m.read_all_registers().unwrap();
+    assert_eq!(registers[0], 0x1100);
+    assert_eq!(registers[0x0f], 0x110f);
+    destroy(rda5807m);
+}
+
+#[test]
+fn displays_register_fields() {
+    let mut registers = [0; REGISTER_COUNT];
+    registers[0x00] = 0x5804;
+    registers[0x02] = 0xc001;
+    registers[0x05] = 0x8882;
+    registers[0x0a] = 0x4432;
+    let dump = RegisterDump::new(registers);
+    assert_eq!(dump.get(0x02), Some(0xc001));
+    assert_eq!(dump.get(0x10), None);
+
+    let out = dump.to_string();
+    let lines: Vec<_> = out.lines().collect();
+    assert_eq!(lines.len(), REGISTER_COUNT);
+    assert_eq!(lines[0x00], "0x00 CHIPID  = 0x5804");
+    assert_eq!(
+        lines[0x02],
+        "0x02 CONFIG  = 0xc001 DHIZ DMUTE CLK_MODE=0 ENABLE"
+    );
+    assert_eq!(
+        lines[0x05],
+        "0x05 VOLUME  = 0x8882 INT_MODE SEEK_MODE=0 SEEK_TH=8 LNA_PORT_SEL=2 LNA_ICSEL=0 VOLUME=2"
+    );
+    assert_eq!(lines[0x0a], "0x0a STATUS  = 0x4432 STC ST READCHAN=50");
+    assert_eq!(lines[0x01], "0x01 RSVD    = 0x0000");
+}
+
+#[test]
+fn refuses_raw_writes_to_read_only_registers() {
+    let expectations = [
+        write(Register::RDA5807M_REG_VOLUME, 0x8882),
+        write(Register::RDA5807M_REG_STATUS, 0x0000),
+    ];
+    let mut rda5807m = new(&expectations);
+    rda5807m
+        .write_raw(Register::RDA5807M_REG_VOLUME, 0x8882)
+        .unwrap();
+    for register in [0x00, 0x0a, 0x0f, 0x10] {
+        assert!(matches!(
+            rda5807m.write_raw(register, 0),
+            Err(Error::ReadOnlyRegister(r)) if r == register
+        ));
+    }
+    rda5807m
+        .write_raw_forced(Register::RDA5807M_REG_STATUS, 0)
+        .unwrap();
+    destroy(rda5807m);
+}
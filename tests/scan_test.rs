@@ -0,0 +1,178 @@
+use embedded_hal_mock::eh0::delay::NoopDelay;
+use embedded_hal_mock::eh0::i2c::Transaction as I2cTransaction;
+use rda5807m::rds::{GroupType, GroupVersion};
+use rda5807m::register_address::{ConfigBitFlags, Register, StatusBitFlag, TuningBitFlag};
+use rda5807m::{ScanMethod, ScanOptions};
+
+use crate::commons::{destroy, new, read, write};
+
+mod commons;
+
+const JAPAN_200_KHZ: u16 = TuningBitFlag::BAND_76_91_MHZ | TuningBitFlag::SPACE_200_KHZ;
+
+fn tune(chan: u16) -> u16 {
+    JAPAN_200_KHZ | chan << TuningBitFlag::CHAN_SHIFT | TuningBitFlag::TUNE
+}
+
+#[test]
+fn can_step_scan() {
+    let mut expectations = vec![read(Register::RDA5807M_REG_TUNING, JAPAN_200_KHZ)];
+    // 76.0 - 91.0MHz with 200kHz spacing has 76 channels
+    for chan in 0..=75u16 {
+        let rssi = if chan == 10 || chan == 42 { 40 } else { 5 };
+        let stereo = if chan == 42 { StatusBitFlag::ST } else { 0 };
+        expectations.push(write(Register::RDA5807M_REG_TUNING, tune(chan)));
+        expectations.push(read(
+            Register::RDA5807M_REG_STATUS,
+            StatusBitFlag::STC | stereo | chan,
+        ));
+        expectations.push(read(Register::RDA5807M_REG_RSSI, rssi << 9));
+    }
+    let mut dev = new(&expectations);
+    let options = ScanOptions {
+        method: ScanMethod::Step,
+        ..Default::default()
+    };
+    let stations = dev.scan_band::<_, 8>(&mut NoopDelay, &options).unwrap();
+    assert_eq!(stations.len(), 2);
+    assert_eq!(stations[0].frequency, 78_000);
+    assert_eq!(stations[0].rssi, 40);
+    assert!(!stations[0].stereo);
+    assert_eq!(stations[1].frequency, 84_400);
+    assert!(stations[1].stereo);
+    assert_eq!(stations[1].pi, None);
+    destroy(dev);
+}
+
+// seek scan expectations after tuning channel 0, which has `channel_0_rssi`:
+// the first seek stops on channel 20 (80.0MHz), the second reaches the band edge
+fn seek_scan_expectations(channel_0_rssi: u16) -> Vec<I2cTransaction> {
+    let config = ConfigBitFlags::DHIZ | ConfigBitFlags::DMUTE | ConfigBitFlags::ENABLE;
+    let seek = config | ConfigBitFlags::SEEKUP | ConfigBitFlags::SEEK | ConfigBitFlags::SKMODE;
+    vec![
+        read(Register::RDA5807M_REG_TUNING, JAPAN_200_KHZ),
+        write(Register::RDA5807M_REG_TUNING, tune(0)),
+        read(
+            Register::RDA5807M_REG_STATUS,
+            StatusBitFlag::STC | StatusBitFlag::ST,
+        ),
+        read(Register::RDA5807M_REG_RSSI, channel_0_rssi << 9),
+        read(Register::RDA5807M_REG_CONFIG, config),
+        write(Register::RDA5807M_REG_CONFIG, seek),
+        read(Register::RDA5807M_REG_STATUS, 0),
+        read(
+            Register::RDA5807M_REG_STATUS,
+            StatusBitFlag::STC | StatusBitFlag::ST | 20,
+        ),
+        read(Register::RDA5807M_REG_RSSI, 30 << 9),
+        read(Register::RDA5807M_REG_CONFIG, config),
+        write(Register::RDA5807M_REG_CONFIG, seek),
+        read(
+            Register::RDA5807M_REG_STATUS,
+            StatusBitFlag::STC | StatusBitFlag::SF | 75,
+        ),
+    ]
+}
+
+#[test]
+fn can_seek_scan() {
+    let mut dev = new(&seek_scan_expectations(5));
+    let stations = dev
+        .scan_band::<_, 8>(&mut NoopDelay, &ScanOptions::default())
+        .unwrap();
+    assert_eq!(stations.len(), 1);
+    assert_eq!(stations[0].frequency, 80_000);
+    assert_eq!(stations[0].rssi, 30);
+    assert!(stations[0].stereo);
+    destroy(dev);
+}
+
+#[test]
+fn seek_scan_finds_station_at_band_start() {
+    let mut dev = new(&seek_scan_expectations(40));
+    let stations = dev
+        .scan_band::<_, 8>(&mut NoopDelay, &ScanOptions::default())
+        .unwrap();
+    let found: Vec<_> = stations
+        .iter()
+        .map(|station| (station.frequency, station.rssi))
+        .collect();
+    assert_eq!(found, [(76_000, 40), (80_000, 30)]);
+    destroy(dev);
+}
+
+// RDS reads while dwelling on a station, `group` is `None` without a group ready
+fn rds_poll(status: u16, rssi: u16, group: Option<[u16; 4]>) -> Vec<I2cTransaction> {
+    let Some(group) = group else {
+        return vec![read(Register::RDA5807M_REG_STATUS, status)];
+    };
+    let mut expectations = vec![
+        read(Register::RDA5807M_REG_STATUS, status | StatusBitFlag::RDSR),
+        read(Register::RDA5807M_REG_RSSI, rssi),
+    ];
+    let registers = [
+        Register::RDA5807M_REG_RDSA,
+        Register::RDA5807M_REG_RDSB,
+        Register::RDA5807M_REG_RDSC,
+        Register::RDA5807M_REG_RDSD,
+    ];
+    for (register, block) in registers.into_iter().zip(group) {
+        expectations.push(read(register, block));
+    }
+    expectations
+}
+
+#[test]
+fn captures_rds_while_dwelling() {
+    let config = ConfigBitFlags::DHIZ | ConfigBitFlags::DMUTE | ConfigBitFlags::ENABLE;
+    let seek = config | ConfigBitFlags::SEEKUP | ConfigBitFlags::SEEK | ConfigBitFlags::SKMODE;
+    let status = StatusBitFlag::STC | StatusBitFlag::ST;
+    let mut expectations = vec![
+        read(Register::RDA5807M_REG_TUNING, JAPAN_200_KHZ),
+        write(Register::RDA5807M_REG_TUNING, tune(0)),
+        read(Register::RDA5807M_REG_STATUS, status),
+        read(Register::RDA5807M_REG_RSSI, 40 << 9),
+        // a group with block A uncorrectable is dropped without reading it
+        read(Register::RDA5807M_REG_STATUS, status | StatusBitFlag::RDSR),
+        read(Register::RDA5807M_REG_RSSI, 40 << 9 | 0b11 << 2),
+    ];
+    expectations.extend(rds_poll(status, 40 << 9, None));
+    // the scan moves on as soon as PI and PS are complete
+    for (segment, chars) in [*b"RA", *b"DI", *b"O ", *b"1 "].into_iter().enumerate() {
+        let b = GroupType::new(0, GroupVersion::A).block_b_bits() | segment as u16;
+        let group = [0xd318, b, 0xe0cd, u16::from_be_bytes(chars)];
+        expectations.extend(rds_poll(status, 40 << 9, Some(group)));
+    }
+    expectations.extend([
+        read(Register::RDA5807M_REG_CONFIG, config),
+        write(Register::RDA5807M_REG_CONFIG, seek),
+        read(Register::RDA5807M_REG_STATUS, status | 20),
+        read(Register::RDA5807M_REG_RSSI, 30 << 9),
+    ]);
+    // no RDS on 80.0MHz, 100ms of polls every 10ms
+    for _ in 0..10 {
+        expectations.extend(rds_poll(status | 20, 30 << 9, None));
+    }
+    expectations.extend([
+        read(Register::RDA5807M_REG_CONFIG, config),
+        write(Register::RDA5807M_REG_CONFIG, seek),
+        read(
+            Register::RDA5807M_REG_STATUS,
+            StatusBitFlag::STC | StatusBitFlag::SF | 75,
+        ),
+    ]);
+    let mut dev = new(&expectations);
+    let options = ScanOptions {
+        rds_dwell_ms: 100,
+        ..Default::default()
+    };
+    let stations = dev.scan_band::<_, 8>(&mut NoopDelay, &options).unwrap();
+    assert_eq!(stations.len(), 2);
+    assert_eq!(stations[0].frequency, 76_000);
+    assert_eq!(stations[0].pi, Some(0xd318));
+    assert_eq!(stations[0].ps, Some(*b"RADIO 1 "));
+    assert_eq!(stations[1].frequency, 80_000);
+    assert_eq!(stations[1].pi, None);
+    assert_eq!(stations[1].ps, None);
+    destroy(dev);
+}
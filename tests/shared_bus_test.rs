@@ -0,0 +1,102 @@
+use embedded_hal::blocking::i2c::Write;
+use embedded_hal_mock::eh0::i2c::{Mock as I2cMock, Transaction as I2cTransaction};
+use rda5807m::register_address::Register;
+use rda5807m::{Address, Rda5708m};
+
+use crate::commons::{read, write};
+
+mod commons;
+
+// another device on the bus, e.g. an RTC
+const OTHER_ADDRESS: u8 = 0x68;
+const VOLUME: u16 = 0x8880;
+
+#[test]
+fn can_borrow_the_bus_per_call() {
+    let expectations = [
+        read(Register::RDA5807M_REG_CHIPID, 0x5804),
+        I2cTransaction::write(OTHER_ADDRESS, vec![0x00, 0x12]),
+        read(Register::RDA5807M_REG_VOLUME, VOLUME),
+        write(Register::RDA5807M_REG_VOLUME, VOLUME | 5),
+    ];
+    let mut i2c = I2cMock::new(&expectations);
+    let radio = Rda5708m::detached(Address::default());
+    assert!(radio.with_i2c(&mut i2c).check_id().unwrap());
+    i2c.write(OTHER_ADDRESS, &[0x00, 0x12]).unwrap();
+    let mut borrowed = radio.with_i2c(&mut i2c);
+    borrowed.set_volume(5).unwrap();
+    i2c.done();
+}
+
+#[cfg(feature = "eh1")]
+mod eh1 {
+    use std::cell::RefCell;
+    use std::sync::Mutex;
+
+    use embedded_hal_1::i2c::I2c;
+    use embedded_hal_bus::i2c::{CriticalSectionDevice, MutexDevice, RefCellDevice};
+    use embedded_hal_mock::eh1::i2c::{Mock as I2cMock, Transaction as I2cTransaction};
+    use rda5807m::register_address::{Register, VolumeBitFlag};
+    use rda5807m::{Address, Eh1I2c, Rda5708m};
+
+    use super::{commons::DEVICE_BASE_RANDOM_ADDRESS, OTHER_ADDRESS, VOLUME};
+
+    fn read_volume() -> I2cTransaction {
+        I2cTransaction::write_read(
+            DEVICE_BASE_RANDOM_ADDRESS,
+            vec![Register::RDA5807M_REG_VOLUME],
+            vec![(VOLUME >> 8) as u8, VOLUME as u8],
+        )
+    }
+
+    fn expectations() -> [I2cTransaction; 5] {
+        [
+            read_volume(),
+            I2cTransaction::write(OTHER_ADDRESS, vec![0x00, 0x12]),
+            read_volume(),
+            I2cTransaction::write(
+                DEVICE_BASE_RANDOM_ADDRESS,
+                vec![
+                    Register::RDA5807M_REG_VOLUME,
+                    (VOLUME >> 8) as u8,
+                    VOLUME as u8 | 5,
+                ],
+            ),
+            I2cTransaction::write(OTHER_ADDRESS, vec![0x00, 0x13]),
+        ]
+    }
+
+    // reads the volume, lets the other device write, then sets the volume
+    fn share<R: I2c, O: I2c>(radio: R, mut other: O) {
+        let mut radio = Rda5708m::new(Eh1I2c::new(radio), Address::default());
+        let volume = radio.get_volume().unwrap();
+        assert_eq!(volume.volume as u16, VOLUME & VolumeBitFlag::VOLUME_MASK);
+        other.write(OTHER_ADDRESS, &[0x00, 0x12]).unwrap();
+        radio.set_volume(5).unwrap();
+        other.write(OTHER_ADDRESS, &[0x00, 0x13]).unwrap();
+    }
+
+    #[test]
+    fn can_share_refcell_bus() {
+        let bus = RefCell::new(I2cMock::new(&expectations()));
+        share(RefCellDevice::new(&bus), RefCellDevice::new(&bus));
+        bus.into_inner().done();
+    }
+
+    #[test]
+    fn can_share_critical_section_bus() {
+        let bus = critical_section::Mutex::new(RefCell::new(I2cMock::new(&expectations())));
+        share(
+            CriticalSectionDevice::new(&bus),
+            CriticalSectionDevice::new(&bus),
+        );
+        bus.into_inner().into_inner().done();
+    }
+
+    #[test]
+    fn can_share_mutex_bus() {
+        let bus = Mutex::new(I2cMock::new(&expectations()));
+        share(MutexDevice::new(&bus), MutexDevice::new(&bus));
+        bus.into_inner().unwrap().done();
+    }
+}
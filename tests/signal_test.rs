@@ -0,0 +1,110 @@
+use rda5807m::register_address::{Register, RssiBitFlag, StatusBitFlag};
+use rda5807m::signal::{SignalMonitorConfig, SignalSample};
+use rda5807m::{SignalMonitor, SignalState};
+
+use crate::commons::{destroy, new, read};
+
+mod commons;
+
+fn sample(rssi: u8) -> SignalSample {
+    SignalSample {
+        rssi,
+        fm_true: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn smooths_rssi() {
+    let mut monitor = SignalMonitor::default();
+    assert_eq!(monitor.update(&sample(40)), Some(SignalState::Good));
+    assert_eq!(monitor.rssi_dbuv(), 40);
+    // weight of a new sample is 1/4
+    assert_eq!(monitor.update(&sample(20)), None);
+    assert_eq!(monitor.rssi_dbuv(), 35);
+    assert_eq!(monitor.quality(), 3);
+}
+
+#[test]
+fn limits_smoothing() {
+    for smoothing in [31, 32, u8::MAX] {
+        let config = SignalMonitorConfig {
+            smoothing,
+            ..Default::default()
+        };
+        let mut monitor = SignalMonitor::new(config);
+        monitor.update(&sample(40));
+        // a new sample has no visible weight
+        monitor.update(&sample(0));
+        monitor.update(&sample(127));
+        assert_eq!(monitor.rssi_dbuv(), 40, "smoothing {smoothing}");
+    }
+}
+
+#[test]
+fn state_changes_with_hysteresis() {
+    let config = SignalMonitorConfig {
+        smoothing: 0,
+        ..Default::default()
+    };
+    let mut monitor = SignalMonitor::new(config);
+    assert_eq!(monitor.update(&sample(31)), Some(SignalState::Good));
+    // within the hysteresis band of the good threshold
+    assert_eq!(monitor.update(&sample(28)), None);
+    assert_eq!(monitor.update(&sample(26)), Some(SignalState::Weak));
+    assert_eq!(monitor.update(&sample(32)), None);
+    assert_eq!(monitor.update(&sample(33)), Some(SignalState::Good));
+    assert_eq!(monitor.update(&sample(5)), Some(SignalState::Lost));
+    assert_eq!(monitor.quality(), 0);
+    assert_eq!(monitor.update(&sample(14)), None);
+    assert_eq!(monitor.update(&sample(15)), Some(SignalState::Weak));
+    assert_eq!(monitor.quality(), 1);
+}
+
+#[test]
+fn tracks_rds_error_rate() {
+    let config = SignalMonitorConfig {
+        smoothing: 1,
+        ..Default::default()
+    };
+    let mut monitor = SignalMonitor::new(config);
+    let mut errors = SignalSample {
+        rds_ready: true,
+        blera: 3,
+        blerb: 3,
+        ..sample(40)
+    };
+    monitor.update(&errors);
+    assert_eq!(monitor.rds_error_rate(), 50);
+    // block errors without a ready group are ignored
+    errors.rds_ready = false;
+    monitor.update(&errors);
+    assert_eq!(monitor.rds_error_rate(), 50);
+    monitor.update(&SignalSample {
+        rds_ready: true,
+        ..sample(40)
+    });
+    assert_eq!(monitor.rds_error_rate(), 25);
+}
+
+#[test]
+fn can_sample_signal() {
+    let expectations = [
+        read(Register::RDA5807M_REG_STATUS, StatusBitFlag::ST | 100),
+        read(
+            Register::RDA5807M_REG_RSSI,
+            42 << RssiBitFlag::RSSI_SHIFT | RssiBitFlag::FM_TRUE | RssiBitFlag::FM_READY | 0b0111,
+        ),
+    ];
+    let mut dev = new(&expectations);
+    let mut monitor = SignalMonitor::default();
+    assert_eq!(
+        dev.sample_signal(&mut monitor).unwrap(),
+        Some(SignalState::Good)
+    );
+    assert!(monitor.stereo());
+    assert!(monitor.fm_true());
+    assert!(monitor.fm_ready());
+    assert_eq!(monitor.rssi_dbuv(), 42);
+    destroy(dev);
+}
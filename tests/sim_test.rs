@@ -0,0 +1,149 @@
+#![cfg(feature = "sim")]
+
+use rda5807m::rds::{GroupType, GroupVersion, RdsDecoder};
+use rda5807m::sim::{SimChip, SimError, SimStation};
+use rda5807m::{Address, Band, Rda5708m, ScanOptions, Spacing};
+
+const PI: u16 = 0xd318;
+
+fn ps_groups(ps: &[u8; 8]) -> Vec<[u16; 4]> {
+    (0..4)
+        .map(|segment| {
+            let b = GroupType::new(0, GroupVersion::A).block_b_bits() | segment as u16;
+            let chars = [ps[segment * 2], ps[segment * 2 + 1]];
+            [PI, b, 0xe0cd, u16::from_be_bytes(chars)]
+        })
+        .collect()
+}
+
+fn chip() -> SimChip {
+    let chip = SimChip::new();
+    chip.add_station(SimStation::new(89_900, 30, false));
+    chip.add_station(SimStation::new(93_100, 45, true).with_rds(&ps_groups(b"BAYERN 3")));
+    chip.add_station(SimStation::new(104_500, 12, true));
+    chip
+}
+
+#[test]
+fn can_tune_and_read_status() {
+    let chip = chip();
+    let mut radio = Rda5708m::new(&chip, Address::default());
+    assert!(radio.check_id().unwrap());
+    radio.start().unwrap();
+    radio.set_frequency(93_100).unwrap();
+    assert!(!radio.get_status().unwrap().stc);
+
+    chip.advance(10);
+    let status = radio.get_status().unwrap();
+    assert!(status.stc);
+    assert!(status.st);
+    assert_eq!(radio.get_frequency().unwrap(), 93_100);
+    assert_eq!(radio.get_rssi().unwrap(), 45);
+    assert_eq!(chip.frequency(), 93_100);
+
+    let mut decoder = RdsDecoder::new();
+    assert_eq!(radio.read_rds_group().unwrap(), None);
+    for _ in 0..4 {
+        chip.advance(88);
+        decoder.decode(&radio.read_rds_group().unwrap().unwrap());
+        // each group is delivered once
+        assert_eq!(radio.read_rds_group().unwrap(), None);
+    }
+    assert_eq!(decoder.pi(), Some(PI));
+    assert_eq!(decoder.ps(), Some(b"BAYERN 3"));
+
+    // injected groups come first
+    chip.inject_rds_group([0x1234, 0x0000, 0, 0]);
+    chip.advance(88);
+    assert_eq!(
+        radio.read_rds_group().unwrap(),
+        Some([0x1234, 0x0000, 0, 0])
+    );
+}
+
+#[test]
+fn can_seek() {
+    let chip = chip();
+    let mut radio = Rda5708m::new(&chip, Address::default());
+    radio.start().unwrap();
+    radio.set_frequency(90_000).unwrap();
+    chip.advance(10);
+
+    radio.seek_up(false).unwrap();
+    // 31 channels up to 93.1MHz
+    chip.advance(31 * 4 - 1);
+    assert!(!radio.get_status().unwrap().stc);
+    chip.advance(1);
+    let status = radio.get_status().unwrap();
+    assert!(status.stc && !status.sf);
+    assert_eq!(radio.get_frequency().unwrap(), 93_100);
+
+    radio.seek_down(false).unwrap();
+    chip.advance(1000);
+    assert_eq!(radio.get_frequency().unwrap(), 89_900);
+    // nothing below, the seek stops at the band edge
+    radio.seek_down(false).unwrap();
+    chip.advance(1000);
+    let status = radio.get_status().unwrap();
+    assert!(status.stc && status.sf);
+    assert_eq!(radio.get_frequency().unwrap(), 87_000);
+}
+
+#[test]
+fn seek_ends_from_channel_past_the_band() {
+    // no station anywhere, so only going around the band ends the seek
+    let chip = SimChip::new();
+    let mut radio = Rda5708m::new(&chip, Address::default());
+    radio.start().unwrap();
+    radio
+        .set_band_and_spacing(Band::Europe, Spacing::Khz25)
+        .unwrap();
+    radio.set_frequency(107_000).unwrap();
+    chip.advance(10);
+    // channel 800 is far past the 76 channels of this band
+    radio
+        .set_band_and_spacing(Band::Japan, Spacing::Khz200)
+        .unwrap();
+    radio.seek_up(true).unwrap();
+    chip.advance(1000);
+    let status = radio.get_status().unwrap();
+    assert!(status.stc && status.sf);
+    assert_eq!(radio.get_frequency().unwrap(), 91_000);
+}
+
+#[test]
+fn can_scan_band() {
+    let chip = chip();
+    let mut radio = Rda5708m::new(&chip, Address::default());
+    radio.start().unwrap();
+    let options = ScanOptions {
+        rds_dwell_ms: 1000,
+        ..Default::default()
+    };
+    let stations = radio
+        .scan_band::<_, 8>(&mut chip.delay(), &options)
+        .unwrap();
+    let found: Vec<_> = stations
+        .iter()
+        .map(|station| (station.frequency, station.stereo, station.pi))
+        .collect();
+    assert_eq!(
+        found,
+        [
+            (89_900, false, None),
+            (93_100, true, Some(PI)),
+            (104_500, true, None)
+        ]
+    );
+    assert_eq!(stations[1].ps, Some(*b"BAYERN 3"));
+}
+
+#[test]
+fn rejects_other_addresses() {
+    let chip = chip();
+    let mut radio = Rda5708m::new(&chip, 0x42);
+    assert!(matches!(
+        radio.check_id(),
+        Err(rda5807m::Error::I2C(SimError::Nack))
+    ));
+}
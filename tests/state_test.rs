@@ -0,0 +1,68 @@
+use rda5807m::register_address::{ConfigBitFlags, Register, StatusBitFlag, TuningBitFlag};
+use rda5807m::{Band, RadioState, Spacing};
+
+use crate::commons::{destroy, new, read, write};
+
+mod commons;
+
+const CONFIG: u16 = ConfigBitFlags::DHIZ
+    | ConfigBitFlags::BASS
+    | ConfigBitFlags::SKMODE
+    | ConfigBitFlags::CLK_12_MHZ
+    | ConfigBitFlags::RDS
+    | ConfigBitFlags::ENABLE;
+const TUNING: u16 = TuningBitFlag::BAND_76_108_MHZ | TuningBitFlag::SPACE_50_KHZ;
+
+#[test]
+fn can_snapshot_and_restore() {
+    let expectations = [
+        read(Register::RDA5807M_REG_CONFIG, CONFIG),
+        read(Register::RDA5807M_REG_TUNING, TUNING),
+        read(Register::RDA5807M_REG_GPIO, 0x0400),
+        read(Register::RDA5807M_REG_VOLUME, 0x88c5),
+        read(Register::RDA5807M_REG_I2S, 0x0000),
+        read(Register::RDA5807M_REG_BLEND, 0x4202),
+        read(Register::RDA5807M_REG_FREQ, 0x0000),
+        read(Register::RDA5807M_REG_STATUS, StatusBitFlag::STC | 514),
+        // restore
+        write(Register::RDA5807M_REG_CONFIG, CONFIG),
+        write(Register::RDA5807M_REG_GPIO, 0x0400),
+        write(Register::RDA5807M_REG_VOLUME, 0x88c5),
+        write(Register::RDA5807M_REG_I2S, 0x0000),
+        write(Register::RDA5807M_REG_BLEND, 0x4202),
+        write(Register::RDA5807M_REG_FREQ, 0x0000),
+        write(
+            Register::RDA5807M_REG_TUNING,
+            TUNING | 514 << TuningBitFlag::CHAN_SHIFT | TuningBitFlag::TUNE,
+        ),
+    ];
+    let mut dev = new(&expectations);
+    let state = dev.snapshot().unwrap();
+    assert_eq!(state.frequency, 101_700);
+    assert_eq!(state.band, Band::WorldWide);
+    assert_eq!(state.spacing, Spacing::Khz50);
+    assert!(state.mute);
+    assert!(state.bass);
+    assert!(!state.mono);
+    assert!(state.seek_stop_at_limit);
+    assert_eq!(state.clock, ConfigBitFlags::CLK_12_MHZ);
+    assert_eq!(state.seek_threshold, 8);
+    assert_eq!(state.volume, 5);
+    dev.restore(&state).unwrap();
+    destroy(dev);
+}
+
+#[test]
+fn registers_round_trip() {
+    let registers = [
+        CONFIG,
+        TUNING | 514 << 6,
+        0x0400,
+        0x88c5,
+        0x0010,
+        0x4202,
+        0x1234,
+    ];
+    let state = RadioState::from_registers(&registers, 514);
+    assert_eq!(state.to_registers(), registers);
+}
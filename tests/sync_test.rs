@@ -0,0 +1,113 @@
+use rda5807m::rds::sync::{
+    correct_block, encode_block, syndrome, BlockQuality, BlockSync, Confidence, CorrectedGroup,
+    Offset, BLOCK_BITS,
+};
+use rda5807m::rds::{GroupType, GroupVersion, RdsDecoder};
+
+const PI: u16 = 0xd318;
+
+fn ps_group(segment: u16, chars: &[u8; 2]) -> [u16; 4] {
+    let b = GroupType::new(0, GroupVersion::A).block_b_bits() | segment;
+    [PI, b, 0xe0cd, u16::from_be_bytes(*chars)]
+}
+
+fn bits(group: &[u16; 4]) -> Vec<u32> {
+    [Offset::A, Offset::B, Offset::C, Offset::D]
+        .iter()
+        .zip(group)
+        .map(|(offset, data)| encode_block(*data, *offset))
+        .collect()
+}
+
+fn push_block(sync: &mut BlockSync, block: u32) -> Option<CorrectedGroup> {
+    let mut group = None;
+    for bit in (0..BLOCK_BITS).rev() {
+        group = group.or(sync.push_bit(block & (1 << bit) != 0));
+    }
+    group
+}
+
+#[test]
+fn can_correct_bursts() {
+    let block = encode_block(0x1234, Offset::B);
+    assert_eq!(syndrome(block), Offset::B.word());
+    assert_eq!(
+        correct_block(block, Offset::B, 2),
+        (0x1234, BlockQuality::Valid)
+    );
+    for shift in 0..BLOCK_BITS - 1 {
+        for burst in [0b1, 0b11] {
+            let (data, quality) = correct_block(block ^ (burst << shift), Offset::B, 2);
+            assert_eq!(data, 0x1234);
+            assert_eq!(quality, BlockQuality::Corrected(burst.count_ones() as u8));
+        }
+    }
+    for shift in 0..BLOCK_BITS - 4 {
+        let (data, _) = correct_block(block ^ (0b10101 << shift), Offset::B, 5);
+        assert_eq!(data, 0x1234);
+    }
+    assert_eq!(
+        correct_block(block ^ 0b10101, Offset::B, 2).1,
+        BlockQuality::Uncorrectable
+    );
+}
+
+#[test]
+fn can_synchronize_bit_stream() {
+    let mut sync = BlockSync::default();
+    // noise before the first group
+    for bit in [true, false, false, true, true, true, false] {
+        assert_eq!(sync.push_bit(bit), None);
+    }
+    assert!(!sync.is_synced());
+
+    let mut groups = Vec::new();
+    for (segment, chars) in [b"BA", b"YE", b"RN", b" 3", b"BA"].iter().enumerate() {
+        let mut blocks = bits(&ps_group(segment as u16 % 4, chars));
+        if segment == 2 {
+            blocks[3] ^= 0b11 << 7;
+        }
+        if segment == 3 {
+            blocks[0] ^= 0b1001_0110_1001;
+        }
+        for block in blocks {
+            groups.extend(push_block(&mut sync, block));
+        }
+    }
+    assert!(sync.is_synced());
+    // sync is acquired with block B of the first group, which lacks PI
+    assert_eq!(groups.len(), 5);
+    assert_eq!(groups[0].confidence(), Confidence::Partial);
+    assert_eq!(groups[1].confidence(), Confidence::High);
+    assert_eq!(groups[2].quality[3], BlockQuality::Corrected(2));
+    assert_eq!(groups[2].confidence(), Confidence::Corrected);
+    assert_eq!(groups[3].quality[0], BlockQuality::Uncorrectable);
+    assert_eq!(groups[3].confidence(), Confidence::Partial);
+
+    let mut decoder = RdsDecoder::new();
+    for group in &groups {
+        decoder.decode_corrected(group);
+    }
+    assert_eq!(decoder.pi(), Some(PI));
+    assert_eq!(decoder.ps(), Some(b"BAYERN 3"));
+}
+
+#[test]
+fn uses_known_pi_for_chip_groups() {
+    let mut decoder = RdsDecoder::new();
+    let group = CorrectedGroup::from_block_errors(ps_group(0, b"BA"), 3, 0);
+    assert_eq!(group.confidence(), Confidence::Partial);
+    // PI unknown yet
+    assert_eq!(decoder.decode_corrected(&group), None);
+
+    decoder.decode_corrected(&CorrectedGroup::from_block_errors(ps_group(1, b"YE"), 1, 0));
+    let mut broken = ps_group(0, b"BA");
+    broken[0] = 0xffff;
+    decoder.decode_corrected(&CorrectedGroup::from_block_errors(broken, 3, 0));
+    assert_eq!(decoder.pi(), Some(PI));
+    assert_eq!(&decoder.ps_raw()[..4], b"BAYE");
+
+    let group = CorrectedGroup::from_block_errors(ps_group(2, b"RN"), 0, 3);
+    assert_eq!(group.confidence(), Confidence::Unusable);
+    assert_eq!(decoder.decode_corrected(&group), None);
+}
@@ -0,0 +1,130 @@
+use rda5807m::rds::tmc::{TmcDirection, TmcField, TmcMessage, TmcService, TMC_AID};
+use rda5807m::rds::{GroupType, GroupVersion, RdsDecoder};
+
+const PI: u16 = 0xd313;
+
+fn group_8a(b: u16, c: u16, d: u16) -> [u16; 4] {
+    [
+        PI,
+        GroupType::new(8, GroupVersion::A).block_b_bits() | b,
+        c,
+        d,
+    ]
+}
+
+// Free format fields packed into two 28 bit continuation chunks
+fn pack(fields: &[(u8, u16, u32)]) -> u64 {
+    let mut data = 0u64;
+    let mut bits = 0;
+    for (label, value, width) in fields {
+        data = data << 4 | *label as u64;
+        data = data << width | *value as u64;
+        bits += 4 + width;
+    }
+    data << (56 - bits)
+}
+
+#[test]
+fn can_decode_single_group_messages() {
+    let mut decoder = RdsDecoder::new();
+    // TMC service announcement: LTN 1, AFI, scope INR
+    let b = GroupType::new(3, GroupVersion::A).block_b_bits() | 8 << 1;
+    decoder.decode(&[PI, b, 1 << 6 | 1 << 5 | 0b1110, TMC_AID]);
+    assert_eq!(
+        decoder.tmc().service(),
+        Some(&TmcService {
+            location_table: 1,
+            afi: true,
+            enhanced_mode: false,
+            scope: 0b1110,
+            sid: None,
+        })
+    );
+
+    // stationary traffic (101), negative direction, extent 2, diversion, DP 1
+    let message = group_8a(1 << 3 | 1, 1 << 15 | 1 << 14 | 2 << 11 | 101, 12345);
+    // sent twice in a row and repeated later
+    for group in [message, message, message] {
+        decoder.decode(&group);
+    }
+    decoder.decode(&[PI, b, 1 << 6 | 1 << 5 | 0b1110, TMC_AID]);
+    decoder.decode(&message);
+
+    assert_eq!(
+        decoder.poll_tmc_message(),
+        Some(TmcMessage {
+            event: 101,
+            location: 12345,
+            extent: 2,
+            direction: TmcDirection::Negative,
+            duration: 1,
+            diversion: true,
+            location_table: Some(1),
+            fields: Default::default(),
+        })
+    );
+    assert_eq!(decoder.poll_tmc_message(), None);
+}
+
+#[test]
+fn can_decode_multi_group_messages() {
+    let mut decoder = RdsDecoder::new();
+    let data = pack(&[
+        (TmcField::ADDITIONAL_EVENT, 1000, 11),
+        (TmcField::LENGTH_OF_ROUTE, 10, 5),
+        (TmcField::SUPPLEMENTARY_INFO, 0x2a, 8),
+        (TmcField::DURATION, 3, 3),
+    ]);
+    let first = (data >> 28) as u32;
+    let second = data as u32 & 0x0fff_ffff;
+    let groups = [
+        // continuity index 5, queuing traffic (108), extent 1
+        group_8a(5, 1 << 15 | 1 << 11 | 108, 4321),
+        group_8a(5, 1 << 14 | 1 << 12 | (first >> 16) as u16, first as u16),
+        group_8a(5, (second >> 16) as u16, second as u16),
+    ];
+
+    // a missing group drops the message
+    decoder.decode(&groups[0]);
+    decoder.decode(&groups[2]);
+    assert_eq!(decoder.poll_tmc_message(), None);
+
+    for group in &groups {
+        decoder.decode(group);
+        decoder.decode(group);
+    }
+    let message = decoder.poll_tmc_message().unwrap();
+    assert_eq!(message.event, 108);
+    assert_eq!(message.location, 4321);
+    assert_eq!(message.extent, 1);
+    assert_eq!(message.direction, TmcDirection::Positive);
+    assert_eq!(message.duration, 3);
+    assert!(!message.diversion);
+    assert_eq!(message.location_table, None);
+    assert_eq!(
+        message.fields.as_slice(),
+        &[
+            TmcField {
+                label: TmcField::ADDITIONAL_EVENT,
+                value: 1000
+            },
+            TmcField {
+                label: TmcField::LENGTH_OF_ROUTE,
+                value: 10
+            },
+            TmcField {
+                label: TmcField::SUPPLEMENTARY_INFO,
+                value: 0x2a
+            },
+            TmcField {
+                label: TmcField::DURATION,
+                value: 3
+            },
+        ]
+    );
+
+    for group in &groups {
+        decoder.decode(group);
+    }
+    assert_eq!(decoder.poll_tmc_message(), None);
+}
@@ -0,0 +1,64 @@
+use embedded_hal_mock::eh0::i2c::Mock as I2cMock;
+use embedded_hal_mock::eh0::MockError;
+use rda5807m::register_address::{Register, TuningBitFlag};
+use rda5807m::trace::{TraceEntry, TraceOp};
+use rda5807m::{Address, Rda5708m, Traced};
+
+use crate::commons::{read, write};
+
+mod commons;
+
+#[test]
+fn records_register_accesses() {
+    let tuning = 100 << TuningBitFlag::CHAN_SHIFT | TuningBitFlag::TUNE;
+    let expectations = [
+        read(Register::RDA5807M_REG_CHIPID, 0x5804),
+        write(Register::RDA5807M_REG_CONFIG, 0),
+        read(Register::RDA5807M_REG_TUNING, 0),
+        write(Register::RDA5807M_REG_TUNING, tuning),
+        read(Register::RDA5807M_REG_CONFIG, 0).with_error(MockError::Io(std::io::ErrorKind::Other)),
+    ];
+    let i2c = Traced::<_, 3>::new(I2cMock::new(&expectations));
+    let mut radio = Rda5708m::new(i2c, Address::default());
+    radio.check_id().unwrap();
+    radio.stop().unwrap();
+    radio.set_frequency(97_000).unwrap();
+    radio.mute(false).unwrap_err();
+
+    let traced = radio.destroy();
+    // the oldest access was dropped
+    let entries: Vec<_> = traced.entries().copied().collect();
+    assert_eq!(
+        entries[0],
+        TraceEntry {
+            op: TraceOp::Read,
+            register: Register::RDA5807M_REG_TUNING,
+            value: 0,
+            failed: false,
+        }
+    );
+
+    let mut out = String::new();
+    traced.dump(&mut out).unwrap();
+    assert_eq!(
+        out,
+        "R TUNING(0x03) = 0x0000 CHAN=0 BAND=0 SPACE=0\n\
+         W TUNING(0x03) = 0x1910 CHAN=100 TUNE BAND=0 SPACE=0\n\
+         R CONFIG(0x02) failed\n"
+    );
+    traced.into_inner().done();
+}
+
+#[test]
+fn records_last_register_addresses() {
+    let expectations = [write(0xfe, 0x0102), write(0xff, 0x0304)];
+    let i2c = Traced::<_, 4>::new(I2cMock::new(&expectations));
+    let mut radio = Rda5708m::new(i2c, Address::default());
+    radio.write_raw_forced(0xfe, 0x0102).unwrap();
+    radio.write_raw_forced(0xff, 0x0304).unwrap();
+
+    let traced = radio.destroy();
+    let registers: Vec<_> = traced.entries().map(|entry| entry.register).collect();
+    assert_eq!(registers, [0xfe, 0xff]);
+    traced.into_inner().done();
+}
@@ -0,0 +1,200 @@
+use embedded_hal_mock::eh0::i2c::{Mock as I2cMock, Transaction as I2cTransaction};
+use rda5807m::rds::{GroupType, GroupVersion, RdsDecoder};
+use rda5807m::register_address::{ConfigBitFlags, Register, StatusBitFlag, TuningBitFlag};
+use rda5807m::traffic::TrafficConfig;
+use rda5807m::{Rda5708m, TrafficAnnouncer, TrafficEvent};
+
+use crate::commons::{destroy, new, read, write};
+
+mod commons;
+
+const PI_TN: u16 = 0xd318;
+const PI_ON: u16 = 0xd312;
+// muted
+const CONFIG: u16 = ConfigBitFlags::DHIZ | ConfigBitFlags::RDS | ConfigBitFlags::ENABLE;
+const TUNING: u16 = TuningBitFlag::BAND_87_108_MHZ | TuningBitFlag::SPACE_100_KHZ;
+// 90.0MHz
+const CHAN: u16 = 30;
+
+fn snapshot() -> Vec<I2cTransaction> {
+    vec![
+        read(Register::RDA5807M_REG_CONFIG, CONFIG),
+        read(Register::RDA5807M_REG_TUNING, TUNING),
+        read(Register::RDA5807M_REG_GPIO, 0),
+        read(Register::RDA5807M_REG_VOLUME, 0x88c3),
+        read(Register::RDA5807M_REG_I2S, 0),
+        read(Register::RDA5807M_REG_BLEND, 0),
+        read(Register::RDA5807M_REG_FREQ, 0),
+        read(Register::RDA5807M_REG_STATUS, StatusBitFlag::STC | CHAN),
+    ]
+}
+
+fn tuned_frequency() -> Vec<I2cTransaction> {
+    vec![
+        read(Register::RDA5807M_REG_TUNING, TUNING),
+        read(Register::RDA5807M_REG_STATUS, StatusBitFlag::STC | CHAN),
+    ]
+}
+
+fn raise_volume() -> Vec<I2cTransaction> {
+    vec![
+        read(Register::RDA5807M_REG_VOLUME, 0x88c3),
+        write(Register::RDA5807M_REG_VOLUME, 0x88ca),
+        read(Register::RDA5807M_REG_CONFIG, CONFIG),
+        write(
+            Register::RDA5807M_REG_CONFIG,
+            CONFIG | ConfigBitFlags::DMUTE,
+        ),
+    ]
+}
+
+fn basic(pi: u16, ta: bool) -> [u16; 4] {
+    let b = GroupType::new(0, GroupVersion::A).block_b_bits() | 1 << 10 | (ta as u16) << 4;
+    [pi, b, 0, 0x2020]
+}
+
+#[test]
+fn handles_announcement_on_current_station() {
+    let mut expectations = snapshot();
+    expectations.extend(raise_volume());
+    expectations.extend([
+        read(Register::RDA5807M_REG_VOLUME, 0x88ca),
+        write(Register::RDA5807M_REG_VOLUME, 0x88c3),
+        read(
+            Register::RDA5807M_REG_CONFIG,
+            CONFIG | ConfigBitFlags::DMUTE,
+        ),
+        write(Register::RDA5807M_REG_CONFIG, CONFIG),
+    ]);
+    let mut dev = new(&expectations);
+    let mut decoder = RdsDecoder::new();
+    let mut announcer = TrafficAnnouncer::default();
+
+    decoder.decode(&basic(PI_TN, false));
+    assert_eq!(announcer.poll(&mut dev, &mut decoder, 100).unwrap(), None);
+    decoder.decode(&basic(PI_TN, true));
+    assert_eq!(
+        announcer.poll(&mut dev, &mut decoder, 100).unwrap(),
+        Some(TrafficEvent::Started {
+            pi: PI_TN,
+            retuned: false
+        })
+    );
+    assert!(announcer.is_active());
+    assert_eq!(announcer.poll(&mut dev, &mut decoder, 100).unwrap(), None);
+    decoder.decode(&basic(PI_TN, false));
+    assert_eq!(
+        announcer.poll(&mut dev, &mut decoder, 100).unwrap(),
+        Some(TrafficEvent::Ended { timed_out: false })
+    );
+    destroy(dev);
+}
+
+// announcement on the linked network at 93.1MHz, ended by a timeout
+fn linked_announcement() -> Vec<I2cTransaction> {
+    let mut expectations = tuned_frequency();
+    expectations.extend(snapshot());
+    // retune to 93.1MHz
+    expectations.extend([
+        read(Register::RDA5807M_REG_TUNING, TUNING),
+        write(
+            Register::RDA5807M_REG_TUNING,
+            TUNING | 61 << TuningBitFlag::CHAN_SHIFT | TuningBitFlag::TUNE,
+        ),
+    ]);
+    expectations.extend(raise_volume());
+    // restore
+    expectations.extend([
+        write(Register::RDA5807M_REG_CONFIG, CONFIG),
+        write(Register::RDA5807M_REG_GPIO, 0),
+        write(Register::RDA5807M_REG_VOLUME, 0x88c3),
+        write(Register::RDA5807M_REG_I2S, 0),
+        write(Register::RDA5807M_REG_BLEND, 0),
+        write(Register::RDA5807M_REG_FREQ, 0),
+        write(
+            Register::RDA5807M_REG_TUNING,
+            TUNING | CHAN << TuningBitFlag::CHAN_SHIFT | TuningBitFlag::TUNE,
+        ),
+    ]);
+    expectations
+}
+
+// while tuned to 90.0MHz the other network is on 93.1MHz
+fn linked_network(decoder: &mut RdsDecoder, ta: bool) {
+    let eon_a = GroupType::new(14, GroupVersion::A).block_b_bits() | 1 << 4;
+    let eon_b = GroupType::new(14, GroupVersion::B).block_b_bits() | 1 << 4 | (ta as u16) << 3;
+    decoder.decode(&[PI_TN, eon_a | 5, 25 << 8 | 56, PI_ON]);
+    decoder.decode(&[PI_TN, eon_b, PI_TN, PI_ON]);
+}
+
+fn time_out_linked_announcement(
+    announcer: &mut TrafficAnnouncer,
+    dev: &mut Rda5708m<I2cMock>,
+    decoder: &mut RdsDecoder,
+) {
+    linked_network(decoder, true);
+    assert_eq!(
+        announcer.poll(dev, decoder, 100).unwrap(),
+        Some(TrafficEvent::Started {
+            pi: PI_ON,
+            retuned: true
+        })
+    );
+    decoder.decode(&basic(PI_ON, true));
+    assert_eq!(announcer.poll(dev, decoder, 600).unwrap(), None);
+    assert_eq!(
+        announcer.poll(dev, decoder, 600).unwrap(),
+        Some(TrafficEvent::Ended { timed_out: true })
+    );
+    assert!(!announcer.is_active());
+}
+
+fn short_announcements() -> TrafficAnnouncer {
+    TrafficAnnouncer::new(TrafficConfig {
+        max_duration_ms: 1000,
+        ..Default::default()
+    })
+}
+
+#[test]
+fn follows_linked_network_until_timeout() {
+    let mut dev = new(&linked_announcement());
+    let mut decoder = RdsDecoder::new();
+    let mut announcer = short_announcements();
+    time_out_linked_announcement(&mut announcer, &mut dev, &mut decoder);
+    destroy(dev);
+}
+
+#[test]
+fn stays_on_program_after_linked_network_timeout() {
+    let mut dev = new(&linked_announcement());
+    let mut decoder = RdsDecoder::new();
+    let mut announcer = short_announcements();
+    time_out_linked_announcement(&mut announcer, &mut dev, &mut decoder);
+
+    // the decoder was reset on restore and knows nothing of the linked network yet
+    decoder.decode(&basic(PI_TN, false));
+    assert_eq!(announcer.poll(&mut dev, &mut decoder, 100).unwrap(), None);
+    // which is still announcing
+    linked_network(&mut decoder, true);
+    assert_eq!(announcer.poll(&mut dev, &mut decoder, 100).unwrap(), None);
+    assert_eq!(announcer.poll(&mut dev, &mut decoder, 100).unwrap(), None);
+    destroy(dev);
+}
+
+#[test]
+fn ignores_linked_network_without_frequency() {
+    // only the tuned frequency is read, nothing is saved or changed
+    let mut expectations = tuned_frequency();
+    expectations.extend(tuned_frequency());
+    let mut dev = new(&expectations);
+    let mut decoder = RdsDecoder::new();
+    let mut announcer = TrafficAnnouncer::default();
+
+    let eon_b = GroupType::new(14, GroupVersion::B).block_b_bits() | 1 << 4 | 1 << 3;
+    decoder.decode(&[PI_TN, eon_b, PI_TN, PI_ON]);
+    assert_eq!(announcer.poll(&mut dev, &mut decoder, 100).unwrap(), None);
+    assert_eq!(announcer.poll(&mut dev, &mut decoder, 100).unwrap(), None);
+    assert!(!announcer.is_active());
+    destroy(dev);
+}
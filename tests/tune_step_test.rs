@@ -0,0 +1,97 @@
+use rda5807m::band::{last_channel, step_channel};
+use rda5807m::register_address::{Register, TuningBitFlag};
+use rda5807m::{Band, Direction, Spacing};
+
+use crate::commons::{destroy, new, read, write};
+
+mod commons;
+
+const BANDS: [Band; 4] = [Band::Europe, Band::Japan, Band::WorldWide, Band::EastEurope];
+const SPACINGS: [Spacing; 4] = [
+    Spacing::Khz100,
+    Spacing::Khz200,
+    Spacing::Khz50,
+    Spacing::Khz25,
+];
+
+#[test]
+fn last_channel_of_each_band() {
+    let expected = [
+        // 100, 200, 50, 25kHz
+        [210, 105, 420, 840],  // 87-108MHz
+        [150, 75, 300, 600],   // 76-91MHz
+        [320, 160, 640, 1023], // 76-108MHz, 25kHz exceeds the 10 bit channel field
+        [110, 55, 220, 440],   // 65-76MHz
+    ];
+    for (band, row) in BANDS.iter().zip(expected) {
+        for (spacing, last) in SPACINGS.iter().zip(row) {
+            assert_eq!(last_channel(*band, *spacing), last, "{band:?} {spacing:?}");
+        }
+    }
+}
+
+#[test]
+fn step_inside_band() {
+    for band in BANDS {
+        for spacing in SPACINGS {
+            for wrap in [false, true] {
+                assert_eq!(step_channel(band, spacing, 10, Direction::Up, 1, wrap), 11);
+                assert_eq!(step_channel(band, spacing, 10, Direction::Down, 3, wrap), 7);
+            }
+        }
+    }
+}
+
+#[test]
+fn step_wraps_at_band_edges() {
+    for band in BANDS {
+        for spacing in SPACINGS {
+            let last = last_channel(band, spacing);
+            assert_eq!(step_channel(band, spacing, last, Direction::Up, 1, true), 0);
+            assert_eq!(step_channel(band, spacing, last, Direction::Up, 3, true), 2);
+            assert_eq!(
+                step_channel(band, spacing, 0, Direction::Down, 1, true),
+                last
+            );
+            assert_eq!(
+                step_channel(band, spacing, 1, Direction::Down, 3, true),
+                last - 1
+            );
+        }
+    }
+}
+
+#[test]
+fn step_clamps_at_band_edges() {
+    for band in BANDS {
+        for spacing in SPACINGS {
+            let last = last_channel(band, spacing);
+            assert_eq!(
+                step_channel(band, spacing, last, Direction::Up, 1, false),
+                last
+            );
+            assert_eq!(
+                step_channel(band, spacing, last - 1, Direction::Up, 5, false),
+                last
+            );
+            assert_eq!(step_channel(band, spacing, 0, Direction::Down, 1, false), 0);
+            assert_eq!(step_channel(band, spacing, 2, Direction::Down, 5, false), 0);
+        }
+    }
+}
+
+#[test]
+fn can_tune_step() {
+    let tuning = TuningBitFlag::BAND_87_108_MHZ | TuningBitFlag::SPACE_100_KHZ;
+    let expectations = [
+        read(Register::RDA5807M_REG_TUNING, tuning),
+        read(Register::RDA5807M_REG_STATUS, 210),
+        write(
+            Register::RDA5807M_REG_TUNING,
+            tuning | 1 << TuningBitFlag::CHAN_SHIFT | TuningBitFlag::TUNE,
+        ),
+    ];
+    let mut dev = new(&expectations);
+    assert_eq!(dev.tune_step(Direction::Up, 2, true).unwrap(), 87_100);
+    destroy(dev);
+}
@@ -0,0 +1,48 @@
+#![cfg(feature = "ufmt")]
+
+use std::convert::Infallible;
+
+use rda5807m::rds::rbds::CallSign;
+use rda5807m::rds::{GroupType, GroupVersion, RdsDecoder};
+use rda5807m::{Address, Error};
+use ufmt::{uDebug, uWrite, uwrite};
+
+struct Out(String);
+
+impl uWrite for Out {
+    type Error = Infallible;
+
+    fn write_str(&mut self, s: &str) -> Result<(), Infallible> {
+        self.0.push_str(s);
+        Ok(())
+    }
+}
+
+fn format(value: &impl uDebug) -> String {
+    let mut out = Out(String::new());
+    uwrite!(out, "{:?}", value).unwrap();
+    out.0
+}
+
+#[test]
+fn formats_driver_types() {
+    assert_eq!(format(&Error::I2C(5u8)), "I2C(5)");
+    assert_eq!(format(&Address::default()), "Address(17)");
+    assert_eq!(
+        format(&CallSign::Letters("KQED".try_into().unwrap())),
+        "Letters(\"KQED\")"
+    );
+}
+
+#[test]
+fn formats_decoded_rds_as_text() {
+    let mut decoder = RdsDecoder::new();
+    for (segment, chars) in b"RADIO \"1\"".chunks(2).take(4).enumerate() {
+        let b = GroupType::new(0, GroupVersion::A).block_b_bits() | segment as u16;
+        decoder.decode(&[0xd318, b, 0, u16::from_be_bytes([chars[0], chars[1]])]);
+    }
+    assert_eq!(
+        format(&decoder),
+        "RdsDecoder { pi: Some(54040), pty: 0, tp: false, ta: false, ps: Some(\"RADIO \\\"1\"), rt: None }"
+    );
+}